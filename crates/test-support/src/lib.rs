@@ -3,7 +3,6 @@ use cpal::{
     BufferSize, InputStreamTimestamp, StreamConfig, StreamInstant, SupportedStreamConfig,
     SupportedStreamConfigRange,
 };
-use hound;
 use itertools::Itertools;
 use parking_lot::Mutex;
 
@@ -21,6 +20,8 @@ static CHANNEL: u16 = 1;
 static SAMPLE_RATE: u32 = 44_100;
 static BUFFER_SIZE: u32 = 1024;
 
+type StreamCallback = Arc<Mutex<dyn FnMut(&[i32], StreamInstant) + Send>>;
+
 #[derive(Clone)]
 pub struct MockDevice {
     pub name: String,
@@ -89,7 +90,7 @@ pub struct OptConfig {
 
 pub struct MockStream {
     pub ltc_wav_file_path: &'static str,
-    pub callback: Arc<Mutex<dyn FnMut(&[i32], StreamInstant) + Send>>,
+    pub callback: StreamCallback,
     pub rx_start_playing: Arc<Mutex<Receiver<()>>>,
 }
 
@@ -101,13 +102,13 @@ impl MockStream {
         MockStream {
             ltc_wav_file_path: "./assets/audio/LTC_01000000_1mins_30fps_44100x24.wav",
             callback: Arc::new(Mutex::new(callback)),
-            rx_start_playing: Arc::clone(&rx_start_playing),
+            rx_start_playing: Arc::clone(rx_start_playing),
         }
     }
 
     fn next_timestamp(timestamp: &Instant) -> StreamInstant {
         let nanos = timestamp.elapsed().as_nanos() as i64;
-        let secs = nanos / 1_000_000_000 as i64;
+        let secs = nanos / 1_000_000_000;
         let subsec_nanos = nanos - secs * 1_000_000_000;
         StreamInstant::new(secs, subsec_nanos as u32)
     }
@@ -0,0 +1,321 @@
+// Corrections to events already written, for triggers mis-fired during a live show. Deleting an
+// event hands its time to the event before it on the same side, as if the trigger had never
+// fired. Patching an event carries its new source over to the transition out of it.
+
+use anyhow::{anyhow, Error};
+use vtc::Timecode;
+
+use crate::edl_writer::{AVChannels, Clip, Dissolve, EditType, Event, Handles, SourceTape, Wipe};
+
+#[derive(Debug, Default)]
+pub struct EditPatch {
+    pub source_tape: Option<String>,
+    pub edit_type: Option<EditType>,
+    pub edit_duration_frames: Option<u32>,
+    pub wipe_num: Option<u32>,
+    pub av_channels: Option<AVChannels>,
+}
+
+// removes the event at `index`. The event before it on the same side plays on over its time,
+// or the event after it starts early when it was the first.
+pub fn delete(events: &mut Vec<Event>, index: usize) -> Result<Event, Error> {
+    if index >= events.len() {
+        return Err(anyhow!("Edit {} has not been written", index + 1));
+    }
+    let removed = events.remove(index);
+    let gone = incoming(&removed).clone();
+    let frames = gone.record_out.frames() - gone.record_in.frames();
+    let (before, after) = events.split_at_mut(index);
+    let prev = before
+        .iter_mut()
+        .rev()
+        .find(|event| same_side(incoming(event).av_channels, gone.av_channels));
+    let next = after
+        .iter_mut()
+        .find(|event| same_side(incoming(event).av_channels, gone.av_channels));
+    match (prev, next) {
+        (Some(prev), next) => {
+            extend_out(incoming_mut(prev), frames)?;
+            if let Some(next) = next {
+                relink(next, Some(incoming(prev)))?;
+            }
+        }
+        (None, Some(next)) => {
+            extend_in(next, frames)?;
+            relink(next, None)?;
+        }
+        (None, None) => (),
+    }
+    Ok(removed)
+}
+
+// changes the source, channels or transition of the event at `index`
+pub fn patch(events: &mut [Event], index: usize, patch: &EditPatch) -> Result<(), Error> {
+    if index >= events.len() {
+        return Err(anyhow!("Edit {} has not been written", index + 1));
+    }
+    let (before, after) = events.split_at_mut(index);
+    let (event, after) = after
+        .split_first_mut()
+        .ok_or_else(|| anyhow!("Edit {} has not been written", index + 1))?;
+    let side = incoming(event).clone();
+    let prev = before
+        .iter()
+        .rev()
+        .find(|prev| same_side(incoming(prev).av_channels, side.av_channels));
+
+    if patch.edit_type.is_some() || patch.edit_duration_frames.is_some() {
+        *event = retype(event, prev, patch)?;
+    }
+    let clip = incoming_mut(event);
+    if let Some(source_tape) = patch.source_tape.as_deref() {
+        clip.source_tape = Some(source_tape).into();
+    }
+    if let Some(av_channels) = patch.av_channels {
+        clip.av_channels = av_channels;
+    }
+    if let Some(next) = after
+        .iter_mut()
+        .find(|next| same_side(incoming(next).av_channels, side.av_channels))
+    {
+        relink(next, Some(incoming(event)))?;
+    }
+    Ok(())
+}
+
+// the clip an event brings in
+pub fn incoming(event: &Event) -> &Clip {
+    match event {
+        Event::Cut(clip) => clip,
+        Event::Dissolve(Dissolve { to, .. }) | Event::Wipe(Wipe { to, .. }) => to,
+        Event::Key(key) => &key.foreground,
+    }
+}
+
+fn incoming_mut(event: &mut Event) -> &mut Clip {
+    match event {
+        Event::Cut(clip) => clip,
+        Event::Dissolve(Dissolve { to, .. }) | Event::Wipe(Wipe { to, .. }) => to,
+        Event::Key(key) => &mut key.foreground,
+    }
+}
+
+// the clip an event transitions or keys from
+fn outgoing_mut(event: &mut Event) -> Option<&mut Clip> {
+    match event {
+        Event::Cut(_) => None,
+        Event::Dissolve(Dissolve { from, .. }) | Event::Wipe(Wipe { from, .. }) => Some(from),
+        Event::Key(key) => Some(&mut key.background),
+    }
+}
+
+// split video and audio lines only hand time to the same side
+pub(crate) fn same_side(a: AVChannels, b: AVChannels) -> bool {
+    (a.video() && b.video()) || (a.audio() > 0 && b.audio() > 0)
+}
+
+fn retype(event: &Event, prev: Option<&Event>, patch: &EditPatch) -> Result<Event, Error> {
+    let edit_type = patch.edit_type.unwrap_or_else(|| event.into());
+    let to = incoming(event).clone();
+    let (from, duration, wipe_num) = match event {
+        Event::Cut(_) => (None, None, None),
+        Event::Dissolve(dissolve) => (
+            Some(dissolve.from.clone()),
+            Some(dissolve.edit_duration_frames),
+            None,
+        ),
+        Event::Wipe(wipe) => (
+            Some(wipe.from.clone()),
+            Some(wipe.edit_duration_frames),
+            Some(wipe.wipe_number),
+        ),
+        Event::Key(key) => {
+            if !matches!(edit_type, EditType::Key) {
+                return Err(anyhow!("Keys can not be patched to another edit type"));
+            }
+            let mut key = key.clone();
+            key.key_in_frames = patch.edit_duration_frames.or(key.key_in_frames);
+            return Ok(Event::Key(key));
+        }
+    };
+    let duration = patch.edit_duration_frames.or(duration);
+    let duration_err = || anyhow!("Edit type '{}' requires edit duration in frames", edit_type);
+    // a cut turned into a transition comes from wherever the source before it had got to
+    let from = from.unwrap_or_else(|| Clip {
+        source_tape: prev.map_or(SourceTape::BL, |prev| incoming(prev).source_tape.clone()),
+        av_channels: prev.map_or(to.av_channels, |prev| incoming(prev).av_channels),
+        source_in: prev.map_or(to.record_in, |prev| incoming(prev).source_out),
+        source_out: prev.map_or(to.record_in, |prev| incoming(prev).source_out),
+        record_out: to.record_in,
+        speed: None,
+        handles: Handles::default(),
+        ..to.clone()
+    });
+    match edit_type {
+        EditType::Cut => Ok(Event::Cut(to)),
+        EditType::Dissolve => Ok(Event::Dissolve(Dissolve {
+            from,
+            to,
+            edit_duration_frames: duration.ok_or_else(duration_err)?,
+        })),
+        EditType::Wipe => Ok(Event::Wipe(Wipe {
+            from,
+            to,
+            wipe_number: patch.wipe_num.or(wipe_num).unwrap_or(1),
+            edit_duration_frames: duration.ok_or_else(duration_err)?,
+        })),
+        EditType::Key => Err(anyhow!("Edits can not be patched into keys")),
+    }
+}
+
+// points the transition or key of an event at the source now before it, which is black when
+// there's none
+fn relink(event: &mut Event, prev: Option<&Clip>) -> Result<(), Error> {
+    let Some(from) = outgoing_mut(event) else {
+        return Ok(());
+    };
+    match prev {
+        Some(prev) => {
+            let frames = from.source_out.frames() - from.source_in.frames();
+            from.source_tape = prev.source_tape.clone();
+            from.av_channels = prev.av_channels;
+            from.source_in = prev.source_out;
+            from.source_out = shift(prev.source_out, frames)?;
+        }
+        None => from.source_tape = SourceTape::BL,
+    }
+    Ok(())
+}
+
+fn extend_out(clip: &mut Clip, frames: i64) -> Result<(), Error> {
+    clip.record_out = shift(clip.record_out, frames)?;
+    match source_frames(clip, frames) {
+        reverse if reverse < 0 => clip.source_in = shift(clip.source_in, reverse)?,
+        forward => clip.source_out = shift(clip.source_out, forward)?,
+    }
+    Ok(())
+}
+
+// starts the event's clips earlier, transitions included
+fn extend_in(event: &mut Event, frames: i64) -> Result<(), Error> {
+    let clip = incoming_mut(event);
+    clip.record_in = shift(clip.record_in, -frames)?;
+    match source_frames(clip, frames) {
+        reverse if reverse < 0 => clip.source_out = shift(clip.source_out, -reverse)?,
+        forward => clip.source_in = shift(clip.source_in, -forward)?,
+    }
+    let record_in = clip.record_in;
+    if let Some(from) = outgoing_mut(event) {
+        let flat = from.record_in == from.record_out;
+        from.record_in = record_in;
+        if flat {
+            from.record_out = record_in;
+        }
+    }
+    Ok(())
+}
+
+// source frames played over record frames, negative in reverse
+fn source_frames(clip: &Clip, frames: i64) -> i64 {
+    match clip.speed {
+        Some(speed) => (frames as f32 * speed / 100.0).round() as i64,
+        None => frames,
+    }
+}
+
+fn shift(tc: Timecode, frames: i64) -> Result<Timecode, Error> {
+    Timecode::with_frames(tc.frames() + frames, tc.rate()).map_err(|_| {
+        anyhow!(
+            "Could not move timecode {} by {} frames",
+            tc.timecode(),
+            frames
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::test::session_events;
+    use vtc::rates;
+
+    fn summary(events: &[Event]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| {
+                let clip = incoming(event);
+                let from = match event {
+                    Event::Dissolve(Dissolve { from, .. }) | Event::Wipe(Wipe { from, .. }) => {
+                        format!("{} > ", <&str>::from(&from.source_tape))
+                    }
+                    _ => String::new(),
+                };
+                format!(
+                    "{}{} {} {}-{}",
+                    from,
+                    <&str>::from(&clip.source_tape),
+                    <&str>::from(EditType::from(event)),
+                    clip.record_in.timecode(),
+                    clip.record_out.timecode()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn delete_events() {
+        // the wipe to cam2 never happened, cam1 plays on and dissolves to itself
+        let mut events = session_events(rates::F24);
+        let removed = delete(&mut events, 1).unwrap();
+        assert_eq!(<&str>::from(&incoming(&removed).source_tape), "cam2");
+        assert_eq!(
+            summary(&events),
+            vec![
+                "camera one long name.mov cut 01:00:00:00-01:00:20:00",
+                "camera one long name.mov > camera one long name.mov dissolve \
+                 01:00:20:00-01:00:30:00",
+            ]
+        );
+        assert_eq!(incoming(&events[0]).source_out.timecode(), "01:00:20:00");
+
+        // without an event before it, the one after starts early from black
+        let mut events = session_events(rates::F24);
+        delete(&mut events, 0).unwrap();
+        assert_eq!(
+            summary(&events)[0],
+            "BL > cam2 wipe 01:00:00:00-01:00:20:00"
+        );
+        assert!(delete(&mut events, 2).is_err());
+    }
+
+    #[test]
+    fn patch_events() {
+        let mut events = session_events(rates::F24);
+        let to_cam3 = EditPatch {
+            source_tape: Some("cam3".into()),
+            edit_type: Some(EditType::Cut),
+            ..Default::default()
+        };
+        patch(&mut events, 1, &to_cam3).unwrap();
+        let to_dissolve = EditPatch {
+            edit_type: Some(EditType::Dissolve),
+            edit_duration_frames: Some(6),
+            ..Default::default()
+        };
+        patch(&mut events, 0, &to_dissolve).unwrap();
+        assert_eq!(
+            summary(&events),
+            vec![
+                "BL > camera one long name.mov dissolve 01:00:00:00-01:00:10:00",
+                "cam3 cut 01:00:10:00-01:00:20:00",
+                "cam3 > camera one long name.mov dissolve 01:00:20:00-01:00:30:00",
+            ]
+        );
+
+        let to_wipe = EditPatch {
+            edit_type: Some(EditType::Wipe),
+            ..Default::default()
+        };
+        assert!(patch(&mut events, 1, &to_wipe).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::edl_writer::{Clip, SourceTape};
+use crate::edl_writer::{Clip, Handles, SourceTape};
 use serde::{
     self,
     de::{self, MapAccess, Visitor},
@@ -22,6 +22,7 @@ impl<'de> Deserialize<'de> for Clip {
             SourceOut,
             RecordIn,
             RecordOut,
+            Speed,
         }
 
         struct ClipVisitor;
@@ -43,6 +44,7 @@ impl<'de> Deserialize<'de> for Clip {
                 let mut source_out = None;
                 let mut record_in = None;
                 let mut record_out = None;
+                let mut speed = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -58,7 +60,7 @@ impl<'de> Deserialize<'de> for Clip {
                             }
                             source_tape = Some(match map.next_value()? {
                                 "BL" => SourceTape::BL,
-                                v @ _ => SourceTape::AX(v.into()),
+                                v => Some(v).into(),
                             });
                         }
                         Field::AVChannels => {
@@ -95,6 +97,12 @@ impl<'de> Deserialize<'de> for Clip {
                             let timecode_str: String = map.next_value()?;
                             record_out = Timecode::with_frames(&timecode_str, rates::F24).ok();
                         }
+                        Field::Speed => {
+                            if speed.is_some() {
+                                return Err(de::Error::duplicate_field("speed"));
+                            }
+                            speed = Some(map.next_value()?);
+                        }
                     }
                 }
 
@@ -109,6 +117,8 @@ impl<'de> Deserialize<'de> for Clip {
                     source_out: source_out.ok_or_else(|| de::Error::missing_field("source_out"))?,
                     record_in: record_in.ok_or_else(|| de::Error::missing_field("record_in"))?,
                     record_out: record_out.ok_or_else(|| de::Error::missing_field("record_out"))?,
+                    speed,
+                    handles: Handles::default(),
                 })
             }
         }
@@ -123,6 +133,7 @@ impl<'de> Deserialize<'de> for Clip {
                 "source_out",
                 "record_in",
                 "record_out",
+                "speed",
             ],
             ClipVisitor,
         )
@@ -0,0 +1,435 @@
+// EDL variants for linear suites and legacy tools that won't take CMX3600. They share the
+// CMX3600 line layout but differ in how many edits, reel characters and audio channels they
+// can hold, and in how audio channels are written. Each dialect maps events to the transition
+// codes it reads, which for these four are the CMX3600 codes listed under "Transition Types" in
+// the EDL guide below.
+// https://www.edlmax.com/EdlMaxHelp/Edl/maxguide.html
+
+use anyhow::{anyhow, Context, Error};
+
+use std::fmt;
+
+use crate::edl_writer::{reel::Reels, AVChannels, Clip, Dissolve, EdlEditLine, Event, Key, Wipe};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dialect {
+    Cmx3600,
+    Cmx340,
+    Gvg4Plus,
+    Sony9100,
+}
+
+impl Dialect {
+    pub const ALL: [Dialect; 4] = [
+        Dialect::Cmx3600,
+        Dialect::Cmx340,
+        Dialect::Gvg4Plus,
+        Dialect::Sony9100,
+    ];
+
+    pub fn edit_number_digits(&self) -> usize {
+        match self {
+            Dialect::Gvg4Plus => 4,
+            _ => 3,
+        }
+    }
+
+    pub fn reel_width(&self) -> usize {
+        match self {
+            Dialect::Cmx3600 => 8,
+            Dialect::Cmx340 => 3,
+            Dialect::Gvg4Plus | Dialect::Sony9100 => 6,
+        }
+    }
+
+    pub fn max_audio(&self) -> u8 {
+        match self {
+            Dialect::Cmx340 => 2,
+            _ => 4,
+        }
+    }
+
+    pub fn wipe_code_digits(&self) -> usize {
+        match self {
+            Dialect::Cmx3600 | Dialect::Cmx340 | Dialect::Gvg4Plus | Dialect::Sony9100 => 3,
+        }
+    }
+
+    fn code(&self, transition: Transition) -> &'static str {
+        match self {
+            Dialect::Cmx3600 | Dialect::Cmx340 | Dialect::Gvg4Plus | Dialect::Sony9100 => {
+                match transition {
+                    Transition::Cut => "C",
+                    Transition::Dissolve => "D",
+                    Transition::Wipe => "W",
+                    Transition::KeyBackground => "K B",
+                    Transition::KeyIn => "K",
+                    Transition::KeyOut => "KO",
+                }
+            }
+        }
+    }
+
+    // the transition codes of the outgoing and incoming lines of an event, padded to the width
+    // of the transition column
+    pub fn transition_codes(&self, edit: &Event) -> Result<(String, String), Error> {
+        let code = |transition| self.transition_code(transition);
+        match edit {
+            Event::Cut(_) => Ok((code(Transition::Cut), "".into())),
+            Event::Dissolve(_) => Ok((code(Transition::Cut), code(Transition::Dissolve))),
+            Event::Wipe(wipe) => {
+                let digits = self.wipe_code_digits();
+                let number = pad_num(wipe.wipe_number, digits).with_context(|| {
+                    format!("{} wipe codes cannot exceed {}", self, max_num(digits))
+                })?;
+                let wipe = format!("{}{number}", self.code(Transition::Wipe));
+                Ok((code(Transition::Cut), format!("{wipe:<4}")))
+            }
+            // a key that only fades out is written as a key out
+            Event::Key(Key {
+                key_in_frames: None,
+                key_out_frames: Some(_),
+                ..
+            }) => Ok((code(Transition::KeyBackground), code(Transition::KeyOut))),
+            Event::Key(_) => Ok((code(Transition::KeyBackground), code(Transition::KeyIn))),
+        }
+    }
+
+    fn transition_code(&self, transition: Transition) -> String {
+        format!("{:<4}", self.code(transition))
+    }
+
+    // audio channels past what the dialect can hold are dropped
+    pub fn channels(&self, av_channels: AVChannels) -> String {
+        let audio = av_channels.audio().min(self.max_audio());
+        let video = av_channels.video();
+        match self {
+            Dialect::Cmx3600 => AVChannels::new(video, audio).into(),
+            // CMX340 writes picture with the first audio channel as B(oth)
+            Dialect::Cmx340 => match (video, audio) {
+                (true, 1) => "B".into(),
+                _ => AVChannels::new(video, audio).into(),
+            },
+            // GVG and Sony number each audio channel, ie. A12/V
+            Dialect::Gvg4Plus | Dialect::Sony9100 => {
+                let audio = match audio {
+                    0 => "".to_string(),
+                    n => (1..=n).fold("A".to_string(), |acc, ch| format!("{acc}{ch}")),
+                };
+                match (video, audio.is_empty()) {
+                    (true, true) => "V".into(),
+                    (true, false) => format!("{audio}/V"),
+                    (false, _) => audio,
+                }
+            }
+        }
+    }
+
+    pub fn edit_number(&self, edit_number: usize) -> Result<String, Error> {
+        let digits = self.edit_number_digits();
+        pad_num(edit_number as u32, digits)
+            .with_context(|| format!("{} cannot exceed {} edits", self, max_num(digits)))
+    }
+
+    // the motion effect of a clip played off speed, with the speed in frames per second
+    pub fn motion(&self, clip: &Clip, reel: &str) -> String {
+        let Some(speed) = clip.speed else {
+            return "".into();
+        };
+        let fps = speed / 100.0 * clip.source_in.rate().timebase().to_integer() as f32;
+        let fps = match fps < 0.0 {
+            true => format!("-{:05.1}", -fps),
+            false => format!("{:05.1}", fps),
+        };
+        format!(
+            "\nM2   {:<width$}      {fps:>6}                {}",
+            reel,
+            clip.source_in.timecode(),
+            width = self.reel_width()
+        )
+    }
+
+    pub fn format_event(&self, edit: &Event, reels: &mut Reels) -> Result<String, Error> {
+        let (cut_one_str, cut_two_str) = self.transition_codes(edit)?;
+        match edit {
+            Event::Cut(clip) => {
+                let from_cmt = clip.source_tape.as_from_clip_name();
+                let from_file = clip.source_tape.as_source_file();
+                let reel = reels.reel(&clip.source_tape)?;
+                let motion = self.motion(clip, &reel);
+                let from: String =
+                    EdlEditLine::from_clip(clip, &reel, cut_one_str, None, *self)?.into();
+                Ok(format!("\n{from}{motion}{from_cmt}{from_file}"))
+            }
+
+            Event::Dissolve(Dissolve {
+                from,
+                to,
+                edit_duration_frames,
+            })
+            | Event::Wipe(Wipe {
+                from,
+                to,
+                edit_duration_frames,
+                ..
+            }) => {
+                let from_cmt = from.source_tape.as_from_clip_name();
+                let to_cmt = to.source_tape.as_to_clip_name();
+                // one per named source, in the same order as the clip names
+                let from_file = from.source_tape.as_source_file();
+                let to_file = to.source_tape.as_source_file();
+                let from_reel = reels.reel(&from.source_tape)?;
+                let to_reel = reels.reel(&to.source_tape)?;
+                let motion = self.motion(to, &to_reel);
+                let from: String =
+                    EdlEditLine::from_clip(from, &from_reel, cut_one_str, None, *self)?.into();
+                let to: String = EdlEditLine::from_clip(
+                    to,
+                    &to_reel,
+                    cut_two_str,
+                    Some(*edit_duration_frames),
+                    *self,
+                )?
+                .into();
+                Ok(format!(
+                    "\n{from}\n{to}{motion}{from_cmt}{to_cmt}{from_file}{to_file}"
+                ))
+            }
+
+            // K B for the background, then K with the key in or KO with the key out
+            Event::Key(Key {
+                background,
+                foreground,
+                key_in_frames,
+                key_out_frames,
+            }) => {
+                let bg_cmt = background.source_tape.as_from_clip_name();
+                let fg_cmt = foreground.source_tape.as_key_clip_name();
+                let bg_file = background.source_tape.as_source_file();
+                let fg_file = foreground.source_tape.as_source_file();
+                let bg_reel = reels.reel(&background.source_tape)?;
+                let fg_reel = reels.reel(&foreground.source_tape)?;
+                let motion = self.motion(foreground, &fg_reel);
+                let bg: String =
+                    EdlEditLine::from_clip(background, &bg_reel, cut_one_str, None, *self)?.into();
+                let fg_duration = match cut_two_str == self.transition_code(Transition::KeyOut) {
+                    true => *key_out_frames,
+                    false => *key_in_frames,
+                };
+                let fg: String =
+                    EdlEditLine::from_clip(foreground, &fg_reel, cut_two_str, fg_duration, *self)?
+                        .into();
+                // a key that fades both ways follows the key in with a key out
+                let key_out = match (key_in_frames, key_out_frames) {
+                    (Some(_), Some(frames)) => {
+                        let line: String = EdlEditLine::from_clip(
+                            foreground,
+                            &fg_reel,
+                            self.transition_code(Transition::KeyOut),
+                            Some(*frames),
+                            *self,
+                        )?
+                        .into();
+                        format!("\n{line}")
+                    }
+                    _ => "".into(),
+                };
+                Ok(format!(
+                    "\n{bg}\n{fg}{key_out}{motion}{bg_cmt}{fg_cmt}{bg_file}{fg_file}"
+                ))
+            }
+        }
+    }
+}
+
+pub(crate) fn pad_num(num: u32, digits: usize) -> Result<String, Error> {
+    match num <= max_num(digits) {
+        true => Ok(format!("{:0digits$}", num)),
+        false => Err(anyhow!("Number too large {num}")),
+    }
+}
+
+fn max_num(digits: usize) -> u32 {
+    10u32.pow(digits as u32) - 1
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Transition {
+    Cut,
+    Dissolve,
+    Wipe,
+    KeyBackground,
+    KeyIn,
+    KeyOut,
+}
+
+impl From<Dialect> for &str {
+    fn from(value: Dialect) -> Self {
+        match value {
+            Dialect::Cmx3600 => "CMX3600",
+            Dialect::Cmx340 => "CMX340",
+            Dialect::Gvg4Plus => "GVG 4 Plus",
+            Dialect::Sony9100 => "Sony 9100",
+        }
+    }
+}
+
+impl TryFrom<&str> for Dialect {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Dialect::ALL
+            .into_iter()
+            .find(|dialect| <&str>::from(*dialect) == value)
+            .ok_or_else(|| anyhow!("Invalid EDL dialect '{}'", value))
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str((*self).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        edl_writer::parser,
+        export::test::{clip, session_events},
+    };
+    use vtc::rates;
+
+    #[test]
+    fn dialect_lines() {
+        let events = session_events(rates::F24);
+        let lines = |dialect: Dialect| -> Vec<String> {
+            let mut reels = Reels::new("", dialect.reel_width()).unwrap();
+            events
+                .iter()
+                .map(|event| dialect.format_event(event, &mut reels).unwrap())
+                .collect()
+        };
+
+        assert_eq!(
+            lines(Dialect::Cmx340)[0],
+            "\n001  CTG    AA/V  C        01:00:00:00 01:00:10:00 01:00:00:00 01:00:10:00\
+             \n* FROM CLIP NAME: camera one long name.mov\
+             \n* SOURCE FILE: camera one long name.mov"
+        );
+        assert_eq!(
+            lines(Dialect::Gvg4Plus)[2],
+            "\n0003  CAM2         V  C        01:00:20:00 01:00:20:00 01:00:20:00 01:00:20:00\
+             \n0003  CAMTG9  A1234/V  D    012 01:00:20:00 01:00:30:00 01:00:20:00 01:00:30:00\
+             \n* FROM CLIP NAME: cam2\
+             \n* TO CLIP NAME: camera one long name.mov\
+             \n* SOURCE FILE: cam2\
+             \n* SOURCE FILE: camera one long name.mov"
+        );
+        assert!(lines(Dialect::Sony9100)[1].contains("\n002  CAM2         V  W012 010 "));
+
+        // each dialect writes its transition codes
+        for dialect in Dialect::ALL {
+            let lines = lines(dialect);
+            assert!(lines[0].contains("  C        01:00:00:00"), "{dialect}");
+            assert!(lines[1].contains("  C        01:00:10:00"), "{dialect}");
+            assert!(lines[1].contains("  W012 010 "), "{dialect}");
+            assert!(lines[2].contains("  D    012 "), "{dialect}");
+        }
+
+        // each dialect reads back in, with channels past its limit dropped
+        for dialect in Dialect::ALL {
+            let edl = lines(dialect).concat();
+            let parsed = parser::parse(&edl, 24.0).unwrap();
+            assert_eq!(parsed.events.len(), 3);
+            let clip: &crate::edl_writer::Clip = (&parsed.events[2]).into();
+            assert_eq!(clip.av_channels.audio(), dialect.max_audio());
+        }
+    }
+
+    #[test]
+    fn transition_codes() {
+        let cam = |tape| {
+            clip(
+                1,
+                Some(tape),
+                AVChannels::new(true, 0),
+                ("01:00:00:00", "01:00:10:00"),
+                rates::F24,
+            )
+        };
+        let key = |key_in_frames, key_out_frames| {
+            Event::Key(Key {
+                background: cam("cam1"),
+                foreground: cam("gfx"),
+                key_in_frames,
+                key_out_frames,
+            })
+        };
+        let wipe = |wipe_number| {
+            Event::Wipe(Wipe {
+                from: cam("cam1"),
+                to: cam("cam2"),
+                wipe_number,
+                edit_duration_frames: 10,
+            })
+        };
+        let codes = |dialect: Dialect, event: &Event| {
+            let (one, two) = dialect.transition_codes(event).unwrap();
+            format!("{one}|{two}")
+        };
+
+        for dialect in Dialect::ALL {
+            assert_eq!(
+                codes(dialect, &Event::Cut(cam("cam1"))),
+                "C   |",
+                "{dialect}"
+            );
+            assert_eq!(codes(dialect, &wipe(7)), "C   |W007", "{dialect}");
+            assert_eq!(codes(dialect, &key(None, None)), "K B |K   ", "{dialect}");
+            assert_eq!(
+                codes(dialect, &key(Some(10), None)),
+                "K B |K   ",
+                "{dialect}"
+            );
+            assert_eq!(
+                codes(dialect, &key(None, Some(10))),
+                "K B |KO  ",
+                "{dialect}"
+            );
+
+            // a key fading both ways is written as a key in then a key out
+            let mut reels = Reels::new("", dialect.reel_width()).unwrap();
+            let lines = dialect
+                .format_event(&key(Some(10), Some(20)), &mut reels)
+                .unwrap();
+            assert!(lines.contains("  K B      01:00:00:00"), "{dialect}");
+            assert!(lines.contains("  K    010 01:00:00:00"), "{dialect}");
+            assert!(lines.contains("  KO   020 01:00:00:00"), "{dialect}");
+
+            assert_eq!(
+                dialect
+                    .transition_codes(&wipe(1000))
+                    .unwrap_err()
+                    .to_string(),
+                format!("{dialect} wipe codes cannot exceed 999")
+            );
+        }
+    }
+
+    #[test]
+    fn dialect_limits() {
+        assert_eq!(
+            Dialect::Cmx340.channels(AVChannels::new(true, 1)),
+            "B".to_string()
+        );
+        assert_eq!(
+            Dialect::Sony9100.channels(AVChannels::new(false, 2)),
+            "A12".to_string()
+        );
+        assert_eq!(Dialect::Gvg4Plus.edit_number(1000).unwrap(), "1000");
+        assert_eq!(
+            Dialect::Cmx3600.edit_number(1000).unwrap_err().to_string(),
+            "CMX3600 cannot exceed 999 edits"
+        );
+    }
+}
@@ -1,74 +1,415 @@
 use anyhow::{anyhow, Error};
+use serde::{
+    ser::{SerializeStruct, Serializer},
+    Serialize,
+};
 use vtc::Timecode;
 
 use std::{collections::VecDeque, fmt};
 
-use crate::edl_writer::{AVChannels, EditType};
+use crate::edl_writer::{
+    correction::{self, EditPatch},
+    midnight, AVChannels, EditType, Event,
+};
 
 // for tracking frame logs in queue.
 // since we have no information about what the out time will be we have to wait
 // until the next log and pop the prior logged value.
+//
+// video and audio each have an open edit. Both share one edit until a split edit changes only
+// one of them, and are brought back together by the next edit that isn't split.
 
 pub struct Edit {
     pub(crate) edit_type: EditType,
     pub(crate) source_tape: Option<String>,
     pub(crate) edit_duration_frames: Option<u32>,
     pub(crate) wipe_num: Option<u32>,
+    pub(crate) key_out_frames: Option<u32>,
     pub(crate) av_channels: AVChannels,
+    pub(crate) split: Option<Split>,
+    pub(crate) motion: Option<Motion>,
     pub(crate) timecode: Timecode,
 }
 
-#[derive(Debug)]
+impl Edit {
+    // a cut to black, as ends a recording
+    pub fn black(timecode: Timecode) -> Self {
+        Edit {
+            edit_type: EditType::Cut,
+            source_tape: None,
+            edit_duration_frames: None,
+            wipe_num: None,
+            key_out_frames: None,
+            av_channels: AVChannels::video_only(),
+            split: None,
+            motion: None,
+            timecode,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct EditQueue {
-    log: VecDeque<OrderedEdit>,
+    open: Open,
+    // edits closed by the last push, waiting to be built into events
+    closed: VecDeque<OrderedEdit>,
+    // the last edit pushed, which closes the edits above
+    last: Option<OrderedEdit>,
     count: usize,
+    event_count: usize,
+    // the record timecode of the first edit for a continuous record timeline, which otherwise
+    // follows the LTC
+    record_start: Option<Timecode>,
+    // the open edit was dropped, the next edit starts where it was on the record timeline
+    gap_left_out: bool,
+    consolidation: Consolidation,
+}
+
+// folds double-taps on the switcher into the edits around them rather than logging each
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Consolidation {
+    // an open edit closed sooner than this is taken over by the edit closing it, 0 to log all
+    pub min_edit_frames: u32,
+    // a cut to the source the open edit is already recording is dropped
+    pub merge_same_source: bool,
+}
+
+// an edit folded into its neighbour instead of being logged on its own
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Merge {
+    // the edit cut to the source already open, which plays on
+    SameSource {
+        source_tape: Option<String>,
+    },
+    // the open edit ran for fewer frames than the minimum, the edit closing it starts in its place
+    ShortEdit {
+        source_tape: Option<String>,
+        frames: i64,
+    },
+}
+
+// a single open edit per queue, not worth boxing
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+enum Open {
+    None,
+    Synced(OrderedEdit),
+    Split {
+        video: OrderedEdit,
+        audio: OrderedEdit,
+    },
 }
 
 impl EditQueue {
     pub fn new() -> Self {
+        EditQueue::with_record_start(None)
+    }
+
+    pub fn with_record_start(record_start: Option<Timecode>) -> Self {
         EditQueue {
-            log: VecDeque::new(),
+            open: Open::None,
+            closed: VecDeque::new(),
+            last: None,
             count: 0,
+            event_count: 0,
+            record_start,
+            gap_left_out: false,
+            consolidation: Consolidation::default(),
+        }
+    }
+
+    pub fn with_consolidation(self, consolidation: Consolidation) -> Self {
+        EditQueue {
+            consolidation,
+            ..self
         }
     }
 
     pub fn push(&mut self, edit: Edit) -> Result<(), Error> {
+        let ordered_edit = self.order(&edit)?;
+        self.gap_left_out = false;
+        self.count += 1;
+
+        let mut closed = Vec::new();
+        let new = ordered_edit.clone();
+        self.open = match (std::mem::replace(&mut self.open, Open::None), edit.split) {
+            (Open::None, _) => Open::Synced(new),
+            (Open::Synced(prev), None) => {
+                closed.push(prev);
+                Open::Synced(new)
+            }
+            (Open::Synced(prev), Some(Split::Video)) => {
+                closed.push(prev.part(Split::Video));
+                Open::Split {
+                    video: new.part(Split::Video),
+                    audio: prev.part(Split::Audio),
+                }
+            }
+            (Open::Synced(prev), Some(Split::Audio)) => {
+                closed.push(prev.part(Split::Audio));
+                Open::Split {
+                    video: prev.part(Split::Video),
+                    audio: new.part(Split::Audio),
+                }
+            }
+            (Open::Split { video, audio }, None) => {
+                closed.extend([video, audio]);
+                Open::Synced(new)
+            }
+            (Open::Split { video, audio }, Some(Split::Video)) => {
+                closed.push(video);
+                Open::Split {
+                    video: new.part(Split::Video),
+                    audio,
+                }
+            }
+            (Open::Split { video, audio }, Some(Split::Audio)) => {
+                closed.push(audio);
+                Open::Split {
+                    video,
+                    audio: new.part(Split::Audio),
+                }
+            }
+        };
+        // ie. the audio side of a video only edit
+        self.closed
+            .extend(closed.into_iter().filter(|edit| !edit.is_empty()));
+        self.last = Some(ordered_edit);
+
+        Ok(())
+    }
+
+    // pushes the edit unless it's folded into the open edit it would close. Only an edit
+    // replacing a whole side is folded, one bringing split video and audio back together
+    // changes both and is always pushed.
+    pub fn push_consolidated(&mut self, edit: Edit) -> Result<Option<Merge>, Error> {
+        let ordered_edit = self.order(&edit)?;
+        let open = match (&mut self.open, edit.split) {
+            (Open::Synced(open), None)
+            | (Open::Split { video: open, .. }, Some(Split::Video))
+            | (Open::Split { audio: open, .. }, Some(Split::Audio)) => open,
+            _ => return self.push(edit).map(|_| None),
+        };
+        let Consolidation {
+            min_edit_frames,
+            merge_same_source,
+        } = self.consolidation;
+        let frames = ordered_edit.timecode.frames() - open.timecode.frames();
+
+        if merge_same_source
+            && matches!(ordered_edit.edit_type, EditType::Cut)
+            && !matches!(open.edit_type, EditType::Key)
+            && ordered_edit.motion.is_none()
+            && open.motion.is_none()
+            && ordered_edit.source_tape == open.source_tape
+            && ordered_edit.av_channels == open.av_channels
+        {
+            return Ok(Some(Merge::SameSource {
+                source_tape: ordered_edit.source_tape,
+            }));
+        }
+        if (0..min_edit_frames as i64).contains(&frames) {
+            let merge = Merge::ShortEdit {
+                source_tape: open.source_tape.clone(),
+                frames,
+            };
+            // the new edit takes the short edit's place on the timeline, from its source before
+            *open = OrderedEdit {
+                timecode: open.timecode,
+                record: open.record,
+                prev_tape: open.prev_tape.clone(),
+                prev_av_channels: open.prev_av_channels,
+                ..ordered_edit
+            };
+            self.last = Some(open.clone());
+            return Ok(Some(merge));
+        }
+        self.push(edit).map(|_| None)
+    }
+
+    // validates the edit and places it after the open edit it replaces
+    fn order(&self, edit: &Edit) -> Result<OrderedEdit, Error> {
         let edit_duration_frames =
             OrderedEdit::validate_edit_type_duration(&edit.edit_type, &edit.edit_duration_frames)?;
         let wipe_num = OrderedEdit::validate_wipe_num(&edit.edit_type, &edit.wipe_num)?;
-        let prev_tape = self.front().and_then(|front| front.source_tape.clone());
-        let prev_av_channels = self
-            .front()
-            .map(|front| front.av_channels)
+        let key_out_frames = OrderedEdit::validate_key(edit)?;
+        let motion = OrderedEdit::validate_motion(edit)?;
+        let av_channels = match edit.split {
+            // keys are laid over the picture
+            None if matches!(edit.edit_type, EditType::Key) => AVChannels::video_only(),
+            None => edit.av_channels,
+            Some(Split::Video) => AVChannels::video_only(),
+            Some(Split::Audio) if edit.av_channels.audio() > 0 => {
+                AVChannels::new(false, edit.av_channels.audio())
+            }
+            Some(Split::Audio) => return Err(anyhow!("Audio split edits need audio channels")),
+        };
+        // the edit being replaced, on the side a split edit changes. An edit bringing both
+        // sides back together transitions from the video side.
+        let prev = match (&self.open, edit.split) {
+            (Open::None, _) => None,
+            (Open::Synced(prev), _) => Some(prev),
+            (Open::Split { video, .. }, None | Some(Split::Video)) => Some(video),
+            (Open::Split { audio, .. }, Some(Split::Audio)) => Some(audio),
+        };
+        let prev_tape = prev.and_then(|prev| prev.source_tape.clone());
+        let prev_av_channels = prev
+            .map(|prev| prev.av_channels)
             .unwrap_or_else(AVChannels::video_only);
+        let timecode = self.unwrap(edit.timecode);
+        let record = self.record_at(timecode)?;
 
-        self.count += 1;
-        self.log.push_back(OrderedEdit {
-            source_tape: edit.source_tape,
-            av_channels: edit.av_channels,
+        Ok(OrderedEdit {
+            source_tape: edit.source_tape.clone(),
+            av_channels,
             edit_type: edit.edit_type,
-            timecode: edit.timecode,
-            edit_number: self.count,
+            timecode,
+            record,
+            // numbered once built into an event, as split edits close out of push order
+            edit_number: 0,
             prev_av_channels,
             prev_tape,
             edit_duration_frames,
             wipe_num,
-        });
+            key_out_frames,
+            motion,
+        })
+    }
 
-        Ok(())
+    // where an LTC frame lands on the record timeline. A continuous timeline runs on by the LTC
+    // since the last edit, and holds still where the LTC jumps back.
+    pub fn record_at(&self, timecode: Timecode) -> Result<Timecode, Error> {
+        let timecode = self.unwrap(timecode);
+        match (self.record_start, self.last.as_ref()) {
+            (None, _) => Ok(timecode),
+            (Some(record_start), None) => Ok(record_start),
+            (Some(_), Some(last)) if self.gap_left_out => Ok(last.record),
+            (Some(_), Some(last)) => {
+                let elapsed = (timecode.frames() - last.timecode.frames()).max(0);
+                Timecode::with_frames(last.record.frames() + elapsed, last.record.rate())
+                    .map_err(|_| anyhow!("Invalid record timecode"))
+            }
+        }
+    }
+
+    // the LTC frame on the timeline the queue counts on past midnight, from the last edit
+    fn unwrap(&self, timecode: Timecode) -> Timecode {
+        match self.last.as_ref() {
+            Some(last) => midnight::unwrap(timecode, last.timecode),
+            None => timecode,
+        }
     }
 
-    pub fn pop_front(&mut self) -> Option<OrderedEdit> {
-        self.log.pop_front()
+    // a queue for another track of the same recording, starting on this queue's record timeline
+    // at the LTC frame
+    pub fn layer(&self, timecode: Timecode) -> Result<EditQueue, Error> {
+        let record_start = match self.record_start {
+            Some(_) => Some(self.record_at(timecode)?),
+            None => None,
+        };
+        Ok(EditQueue::with_record_start(record_start).with_consolidation(self.consolidation))
+    }
+
+    // drops the open edit without writing it, ie. the black over a paused recording
+    pub fn leave_out_gap(&mut self) {
+        self.open = Open::None;
+        self.gap_left_out = true;
+    }
+
+    // the next edit closed by the last push, numbered as the next event
+    pub fn pop_closed(&mut self) -> Option<OrderedEdit> {
+        let mut edit = self.closed.pop_front()?;
+        self.event_count += 1;
+        edit.edit_number = self.event_count;
+        Some(edit)
+    }
+
+    // numbers the next event on from `event_count`, ie. once written events have been deleted
+    pub fn renumber(&mut self, event_count: usize) {
+        self.event_count = event_count;
+    }
+
+    // points each open edit back at the last of `events` on its side, once written events have
+    // been corrected. The open edit transitions from that source when it's closed.
+    pub fn relink(&mut self, events: &[Event]) {
+        let open = match &mut self.open {
+            Open::None => vec![],
+            Open::Synced(edit) => vec![edit],
+            Open::Split { video, audio } => vec![video, audio],
+        };
+        for edit in open {
+            let prev = events
+                .iter()
+                .rev()
+                .map(correction::incoming)
+                .find(|clip| correction::same_side(clip.av_channels, edit.av_channels));
+            edit.prev_tape = prev.and_then(|clip| (&clip.source_tape).into());
+            edit.prev_av_channels =
+                prev.map_or_else(AVChannels::video_only, |clip| clip.av_channels);
+        }
+    }
+
+    // the edits waiting on the next edit for their out point, both sides while video and audio
+    // are split
+    pub fn open(&self) -> Vec<&OrderedEdit> {
+        match &self.open {
+            Open::None => vec![],
+            Open::Synced(edit) => vec![edit],
+            Open::Split { video, audio } => vec![video, audio],
+        }
+    }
+
+    // corrects the edit opened by the last push before the next edit closes it
+    pub fn amend_open(&mut self, patch: &EditPatch) -> Result<Vec<&OrderedEdit>, Error> {
+        let last = self
+            .last
+            .as_mut()
+            .ok_or_else(|| anyhow!("No edit is open"))?;
+        let open = match &mut self.open {
+            Open::None => return Err(anyhow!("No edit is open")),
+            Open::Synced(edit) => edit,
+            // the side a split edit opened keeps its channels
+            Open::Split { .. } if patch.av_channels.is_some() => {
+                return Err(anyhow!("AV channels of a split edit can not be changed"))
+            }
+            Open::Split { video, .. } if video.timecode == last.timecode => video,
+            Open::Split { audio, .. } => audio,
+        };
+        let amended = open.amended(patch)?;
+        *last = last.amended(patch)?;
+        *open = amended;
+        Ok(self.open())
     }
 
     pub fn front(&self) -> Option<&OrderedEdit> {
-        self.log.front()
+        self.last.as_ref()
     }
 
     pub fn clear(&mut self) {
-        self.count = 0;
-        self.log.clear();
+        *self =
+            EditQueue::with_record_start(self.record_start).with_consolidation(self.consolidation);
+    }
+}
+
+impl fmt::Display for Merge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tape = |source_tape: &Option<String>| source_tape.clone().unwrap_or("black".into());
+        match self {
+            Merge::SameSource { source_tape } => {
+                write!(f, "cut to {} already recording", tape(source_tape))
+            }
+            Merge::ShortEdit {
+                source_tape,
+                frames,
+            } => write!(
+                f,
+                "{} frame edit to {} taken over by the next",
+                frames,
+                tape(source_tape)
+            ),
+        }
     }
 }
 
@@ -78,6 +419,48 @@ impl Default for EditQueue {
     }
 }
 
+// the source of an edit played off speed, written as a motion effect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motion {
+    // percent of play speed, negative in reverse and 0 for a freeze frame
+    pub speed: f32,
+    // the source timecode the motion starts from, ie. LTC from a replay deck
+    pub source_in: Timecode,
+}
+
+// which side of an edit a split edit changes, the other keeps recording its current source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Split {
+    Video,
+    Audio,
+}
+
+impl TryFrom<&str> for Split {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            s if s.eq_ignore_ascii_case("video") => Ok(Split::Video),
+            s if s.eq_ignore_ascii_case("audio") => Ok(Split::Audio),
+            _ => Err(anyhow!("invalid split")),
+        }
+    }
+}
+
+impl From<Split> for &str {
+    fn from(value: Split) -> Self {
+        match value {
+            Split::Video => "video",
+            Split::Audio => "audio",
+        }
+    }
+}
+
+impl fmt::Display for Split {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str((*self).into())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OrderedEdit {
     pub(crate) edit_number: usize,
@@ -87,17 +470,82 @@ pub struct OrderedEdit {
     pub(crate) av_channels: AVChannels,
     pub(crate) prev_av_channels: AVChannels,
     pub(crate) timecode: Timecode,
+    // where the edit lands on the record timeline
+    pub(crate) record: Timecode,
     pub(crate) edit_duration_frames: Option<u32>,
     pub(crate) wipe_num: Option<u32>,
+    pub(crate) key_out_frames: Option<u32>,
+    pub(crate) motion: Option<Motion>,
 }
 
 impl OrderedEdit {
+    // the video or audio channels of the edit. A transition out of a source without any of
+    // those channels comes from the same channels of black.
+    fn part(&self, split: Split) -> Self {
+        let part = |av_channels: AVChannels| match split {
+            Split::Video => AVChannels::new(av_channels.video(), 0),
+            Split::Audio => AVChannels::new(false, av_channels.audio()),
+        };
+        let av_channels = part(self.av_channels);
+        let (prev_tape, prev_av_channels) = match part(self.prev_av_channels) {
+            prev if prev.video() || prev.audio() > 0 => (self.prev_tape.clone(), prev),
+            _ => (None, av_channels),
+        };
+        OrderedEdit {
+            av_channels,
+            prev_tape,
+            prev_av_channels,
+            ..self.clone()
+        }
+    }
+
+    // the edit with a correction made to it before it's closed
+    fn amended(&self, patch: &EditPatch) -> Result<Self, Error> {
+        let edit_type = patch.edit_type.unwrap_or(self.edit_type);
+        let key = matches!(self.edit_type, EditType::Key);
+        if key != matches!(edit_type, EditType::Key) {
+            return Err(anyhow!(
+                "Keys can not be changed to or from another edit type"
+            ));
+        }
+        if key && patch.av_channels.is_some() {
+            return Err(anyhow!(
+                "Keys are laid over the picture, their AV channels can not be changed"
+            ));
+        }
+        let edit_duration_frames = OrderedEdit::validate_edit_type_duration(
+            &edit_type,
+            &patch.edit_duration_frames.or(self.edit_duration_frames),
+        )?;
+        let wipe_num = match edit_type {
+            EditType::Wipe => Some(patch.wipe_num.or(self.wipe_num).unwrap_or(1)),
+            _ => None,
+        };
+        Ok(OrderedEdit {
+            edit_type,
+            source_tape: patch
+                .source_tape
+                .clone()
+                .or_else(|| self.source_tape.clone()),
+            av_channels: patch.av_channels.unwrap_or(self.av_channels),
+            edit_duration_frames,
+            wipe_num,
+            ..self.clone()
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.av_channels.video() && self.av_channels.audio() == 0
+    }
+
     fn validate_edit_type_duration(
         edit_type: &EditType,
         edit_duration_frames: &Option<u32>,
     ) -> Result<Option<u32>, Error> {
         match edit_type {
             EditType::Cut => Ok(None),
+            // a key without a duration cuts in
+            EditType::Key => Ok(*edit_duration_frames),
             EditType::Wipe | EditType::Dissolve => edit_duration_frames
                 .ok_or_else(|| {
                     anyhow!("Edit type '{}' requires edit duration in frames", edit_type)
@@ -106,6 +554,35 @@ impl OrderedEdit {
         }
     }
 
+    fn validate_key(edit: &Edit) -> Result<Option<u32>, Error> {
+        match edit.edit_type {
+            EditType::Key if edit.source_tape.is_none() => Err(anyhow!(
+                "Edit type '{}' requires a source tape",
+                edit.edit_type
+            )),
+            EditType::Key if edit.split.is_some() => {
+                Err(anyhow!("Edit type '{}' can not be split", edit.edit_type))
+            }
+            EditType::Key => Ok(edit.key_out_frames),
+            _ => Ok(None),
+        }
+    }
+
+    // motion effect speeds are written in frames per second, up to 999.9
+    fn validate_motion(edit: &Edit) -> Result<Option<Motion>, Error> {
+        let timebase = edit.timecode.rate().timebase().to_integer() as f32;
+        match edit.motion {
+            // the source plays as recorded
+            Some(Motion { speed: 100.0, .. }) => Ok(None),
+            Some(Motion { speed, .. })
+                if !speed.is_finite() || speed.abs() / 100.0 * timebase >= 1000.0 =>
+            {
+                Err(anyhow!("Speed {speed}% is out of range"))
+            }
+            motion => Ok(motion),
+        }
+    }
+
     fn validate_wipe_num(
         edit_type: &EditType,
         wipe_num: &Option<u32>,
@@ -119,6 +596,27 @@ impl OrderedEdit {
     }
 }
 
+impl Serialize for OrderedEdit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("OrderedEdit", 10)?;
+        state.serialize_field("edit_type", <&str>::from(self.edit_type))?;
+        state.serialize_field("source_tape", &self.source_tape)?;
+        state.serialize_field("av_channels", &self.av_channels)?;
+        // the source the edit transitions or keys from
+        state.serialize_field("prev_tape", &self.prev_tape)?;
+        state.serialize_field("edit_duration_frames", &self.edit_duration_frames)?;
+        state.serialize_field("wipe_num", &self.wipe_num)?;
+        state.serialize_field("key_out_frames", &self.key_out_frames)?;
+        state.serialize_field("timecode", &self.timecode.timecode())?;
+        state.serialize_field("record_in", &self.record.timecode())?;
+        state.serialize_field("speed", &self.motion.map(|motion| motion.speed))?;
+        state.end()
+    }
+}
+
 impl TryFrom<&str> for EditType {
     type Error = Error;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
@@ -126,6 +624,7 @@ impl TryFrom<&str> for EditType {
             s if s.eq_ignore_ascii_case("cut") => Ok(EditType::Cut),
             s if s.eq_ignore_ascii_case("wipe") => Ok(EditType::Wipe),
             s if s.eq_ignore_ascii_case("dissolve") => Ok(EditType::Dissolve),
+            s if s.eq_ignore_ascii_case("key") => Ok(EditType::Key),
             _ => Err(anyhow!("invalid edit type")),
         }
     }
@@ -137,6 +636,7 @@ impl From<EditType> for &str {
             EditType::Cut => "cut",
             EditType::Wipe => "wipe",
             EditType::Dissolve => "dissolve",
+            EditType::Key => "key",
         }
     }
 }
@@ -159,8 +659,11 @@ mod test {
             edit_type: "Cut".try_into().unwrap(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("test_1".into()),
             av_channels: AVChannels::default(),
+            split: None,
+            motion: None,
             timecode: Timecode::with_frames("01:00:00:00", vtc::rates::F24).unwrap(),
         };
 
@@ -168,8 +671,11 @@ mod test {
             edit_type: "WiPe".try_into().unwrap(),
             edit_duration_frames: Some(1),
             wipe_num: Some(1),
+            key_out_frames: None,
             source_tape: Some("test_2".into()),
             av_channels: AVChannels::default(),
+            split: None,
+            motion: None,
             timecode: Timecode::with_frames("01:00:10:00", vtc::rates::F24).unwrap(),
         };
 
@@ -186,8 +692,11 @@ mod test {
             edit_type: EditType::Cut,
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("test_1".into()),
             av_channels: AVChannels::default(),
+            split: None,
+            motion: None,
             timecode: Timecode::with_frames("01:00:00:00", vtc::rates::F24).unwrap(),
         };
         assert!(queue.push(edit).is_ok());
@@ -196,28 +705,37 @@ mod test {
             edit_type: EditType::Wipe,
             edit_duration_frames: None, //invalid
             wipe_num: Some(1),
+            key_out_frames: None,
             source_tape: Some("test_2".into()),
             av_channels: AVChannels::default(),
+            split: None,
+            motion: None,
             timecode: Timecode::with_frames("01:00:10:00", vtc::rates::F24).unwrap(),
         };
-        assert!(!queue.push(edit).is_ok());
+        assert!(queue.push(edit).is_err());
 
         let edit = Edit {
             edit_type: EditType::Wipe,
             edit_duration_frames: Some(1),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("test_3".into()),
             av_channels: AVChannels::default(),
+            split: None,
+            motion: None,
             timecode: Timecode::with_frames("01:00:11:01", vtc::rates::F24).unwrap(),
         };
-        assert!(!queue.push(edit).is_ok());
+        assert!(queue.push(edit).is_err());
 
         let edit = Edit {
             edit_type: EditType::Cut,
             edit_duration_frames: Some(1), //ignored
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("test_4".into()),
             av_channels: AVChannels::default(),
+            split: None,
+            motion: None,
             timecode: Timecode::with_frames("01:00:11:01", vtc::rates::F24).unwrap(),
         };
         assert!(queue.push(edit).is_ok());
@@ -226,8 +744,11 @@ mod test {
             edit_type: EditType::Cut,
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None, // valid
             av_channels: AVChannels::default(),
+            split: None,
+            motion: None,
             timecode: Timecode::with_frames("01:00:11:01", vtc::rates::F24).unwrap(),
         };
         assert!(queue.push(edit).is_ok());
@@ -236,8 +757,11 @@ mod test {
             edit_type: EditType::Dissolve,
             edit_duration_frames: Some(9),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None, // valid
             av_channels: AVChannels::default(),
+            split: None,
+            motion: None,
             timecode: Timecode::with_frames("01:00:11:01", vtc::rates::F24).unwrap(),
         };
         assert!(queue.push(edit).is_ok());
@@ -246,12 +770,264 @@ mod test {
             edit_type: EditType::Cut,
             edit_duration_frames: Some(1), //ignored
             wipe_num: Some(1),             //ignored
+            key_out_frames: None,
             source_tape: Some("test_1".into()),
             av_channels: AVChannels::default(),
+            split: None,
+            motion: None,
             timecode: Timecode::with_frames("01:00:00:00", vtc::rates::F24).unwrap(),
         };
         assert!(queue.push(edit).is_ok());
 
         assert_eq!(queue.count, 5);
     }
+
+    #[test]
+    fn split_video_and_audio() {
+        let mut queue = EditQueue::new();
+        let mut push = |tape: &str, split: Option<Split>, tc: &str| {
+            queue
+                .push(Edit {
+                    edit_type: EditType::Cut,
+                    edit_duration_frames: None,
+                    wipe_num: None,
+                    key_out_frames: None,
+                    motion: None,
+                    source_tape: Some(tape.into()),
+                    av_channels: AVChannels::default(),
+                    split,
+                    timecode: Timecode::with_frames(tc, vtc::rates::F24).unwrap(),
+                })
+                .unwrap();
+            let mut closed = Vec::new();
+            while let Some(edit) = queue.pop_closed() {
+                closed.push((
+                    edit.edit_number,
+                    edit.source_tape.unwrap(),
+                    edit.av_channels,
+                    edit.timecode.timecode(),
+                ));
+            }
+            closed
+        };
+
+        assert!(push("a", None, "01:00:00:00").is_empty());
+        // video cuts to b, a's audio carries on under it
+        assert_eq!(
+            push("b", Some(Split::Video), "01:00:10:00"),
+            vec![(
+                1,
+                "a".into(),
+                AVChannels::video_only(),
+                "01:00:00:00".into()
+            )]
+        );
+        assert_eq!(
+            push("b", Some(Split::Audio), "01:00:12:00"),
+            vec![(
+                2,
+                "a".into(),
+                AVChannels::new(false, 2),
+                "01:00:00:00".into()
+            )]
+        );
+        assert_eq!(
+            push("c", None, "01:00:20:00"),
+            vec![
+                (
+                    3,
+                    "b".into(),
+                    AVChannels::video_only(),
+                    "01:00:10:00".into()
+                ),
+                (
+                    4,
+                    "b".into(),
+                    AVChannels::new(false, 2),
+                    "01:00:12:00".into()
+                ),
+            ]
+        );
+        assert_eq!(
+            push("d", None, "01:00:30:00"),
+            vec![(5, "c".into(), AVChannels::default(), "01:00:20:00".into())]
+        );
+    }
+
+    #[test]
+    fn continuous_record_timeline() {
+        let tc = |tc: &str| Timecode::with_frames(tc, vtc::rates::F24).unwrap();
+        let mut queue = EditQueue::with_record_start(Some(tc("10:00:00:00")));
+        let mut push = |tc: Timecode| {
+            queue
+                .push(Edit {
+                    edit_type: EditType::Cut,
+                    edit_duration_frames: None,
+                    wipe_num: None,
+                    key_out_frames: None,
+                    motion: None,
+                    source_tape: Some("a".into()),
+                    av_channels: AVChannels::default(),
+                    split: None,
+                    timecode: tc,
+                })
+                .unwrap();
+            queue.front().unwrap().record.timecode()
+        };
+
+        assert_eq!(push(tc("01:00:00:00")), "10:00:00:00");
+        assert_eq!(push(tc("01:00:10:00")), "10:00:10:00");
+        // the LTC jumps ahead then back, record carries on from where it was
+        assert_eq!(push(tc("05:00:00:00")), "14:00:00:00");
+        assert_eq!(push(tc("02:00:00:00")), "14:00:00:00");
+        assert_eq!(push(tc("02:00:05:00")), "14:00:05:00");
+
+        queue.clear();
+        assert_eq!(queue.record_start, Some(tc("10:00:00:00")));
+    }
+
+    #[test]
+    fn amend_open_edit() {
+        let tc = |tc: &str| Timecode::with_frames(tc, vtc::rates::F24).unwrap();
+        let mut queue = EditQueue::new();
+        assert!(queue.amend_open(&EditPatch::default()).is_err());
+        let push = |queue: &mut EditQueue, tape: &str, split: Option<Split>, tc: Timecode| {
+            queue
+                .push(Edit {
+                    edit_type: EditType::Cut,
+                    edit_duration_frames: None,
+                    wipe_num: None,
+                    key_out_frames: None,
+                    motion: None,
+                    source_tape: Some(tape.into()),
+                    av_channels: AVChannels::default(),
+                    split,
+                    timecode: tc,
+                })
+                .unwrap();
+        };
+        push(&mut queue, "a", None, tc("01:00:00:00"));
+        push(&mut queue, "b", None, tc("01:00:10:00"));
+
+        // b was meant to be a dissolve to c
+        let patch = EditPatch {
+            source_tape: Some("c".into()),
+            edit_type: Some(EditType::Dissolve),
+            edit_duration_frames: Some(12),
+            ..Default::default()
+        };
+        let open = queue.amend_open(&patch).unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].source_tape.as_deref(), Some("c"));
+        assert_eq!(open[0].prev_tape.as_deref(), Some("a"));
+        assert_eq!(open[0].edit_duration_frames, Some(12));
+        assert!(queue
+            .amend_open(&EditPatch {
+                edit_type: Some(EditType::Key),
+                ..Default::default()
+            })
+            .is_err());
+
+        // only the side the split edit opened is amended
+        push(&mut queue, "d", Some(Split::Audio), tc("01:00:20:00"));
+        let patch = EditPatch {
+            source_tape: Some("e".into()),
+            ..Default::default()
+        };
+        let open = queue.amend_open(&patch).unwrap();
+        assert_eq!(open[0].source_tape.as_deref(), Some("c"));
+        assert_eq!(open[1].source_tape.as_deref(), Some("e"));
+        let patch = EditPatch {
+            av_channels: Some(AVChannels::default()),
+            ..Default::default()
+        };
+        assert!(queue.amend_open(&patch).is_err());
+    }
+
+    #[test]
+    fn leave_out_gap() {
+        let tc = |tc: &str| Timecode::with_frames(tc, vtc::rates::F24).unwrap();
+        let mut queue = EditQueue::with_record_start(Some(tc("10:00:00:00")));
+        let push = |queue: &mut EditQueue, tc: Timecode| {
+            queue.push(Edit::black(tc)).unwrap();
+            queue.pop_closed().map(|edit| edit.record.timecode())
+        };
+        push(&mut queue, tc("01:00:00:00"));
+        // paused at 01:00:10:00 and resumed ten minutes later
+        assert_eq!(push(&mut queue, tc("01:00:10:00")).unwrap(), "10:00:00:00");
+        queue.leave_out_gap();
+        assert!(queue.open().is_empty());
+        assert!(push(&mut queue, tc("01:10:10:00")).is_none());
+        assert_eq!(queue.front().unwrap().record.timecode(), "10:00:10:00");
+        assert_eq!(push(&mut queue, tc("01:10:15:00")).unwrap(), "10:00:10:00");
+        assert_eq!(queue.front().unwrap().record.timecode(), "10:00:15:00");
+    }
+
+    #[test]
+    fn consolidate_double_taps() {
+        let tc = |tc: &str| Timecode::with_frames(tc, vtc::rates::F24).unwrap();
+        let mut queue = EditQueue::new().with_consolidation(Consolidation {
+            min_edit_frames: 3,
+            merge_same_source: true,
+        });
+        let push = |queue: &mut EditQueue, tape: &str, split: Option<Split>, tc: Timecode| {
+            let merge = queue
+                .push_consolidated(Edit {
+                    source_tape: Some(tape.into()),
+                    av_channels: AVChannels::default(),
+                    split,
+                    ..Edit::black(tc)
+                })
+                .unwrap();
+            let closed = std::iter::from_fn(|| queue.pop_closed())
+                .map(|edit| format!("{} {}", edit.source_tape.unwrap(), edit.timecode.timecode()))
+                .collect::<Vec<_>>();
+            (merge, closed)
+        };
+
+        assert_eq!(
+            push(&mut queue, "a", None, tc("01:00:00:00")),
+            (None, vec![])
+        );
+        // a second tap on the source already recording
+        assert_eq!(
+            push(&mut queue, "a", None, tc("01:00:05:00")),
+            (
+                Some(Merge::SameSource {
+                    source_tape: Some("a".into())
+                }),
+                vec![]
+            )
+        );
+        assert_eq!(
+            push(&mut queue, "b", None, tc("01:00:10:00")),
+            (None, vec!["a 01:00:00:00".into()])
+        );
+        // b flashes up for two frames before c
+        assert_eq!(
+            push(&mut queue, "c", None, tc("01:00:10:02")),
+            (
+                Some(Merge::ShortEdit {
+                    source_tape: Some("b".into()),
+                    frames: 2
+                }),
+                vec![]
+            )
+        );
+        let open = queue.open();
+        assert_eq!(open[0].source_tape.as_deref(), Some("c"));
+        assert_eq!(open[0].prev_tape.as_deref(), Some("a"));
+        assert_eq!(open[0].timecode, tc("01:00:10:00"));
+        assert_eq!(
+            push(&mut queue, "d", None, tc("01:00:10:03")),
+            (None, vec!["c 01:00:10:00".into()])
+        );
+
+        // bringing split sides back together is always logged
+        push(&mut queue, "e", Some(Split::Audio), tc("01:00:20:00"));
+        assert_eq!(
+            push(&mut queue, "f", None, tc("01:00:20:01")),
+            (None, vec!["d 01:00:10:03".into(), "e 01:00:20:00".into()])
+        );
+    }
 }
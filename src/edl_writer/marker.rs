@@ -0,0 +1,142 @@
+// Markers dropped on the record timeline without making an edit, ie. to flag a great moment or an
+// audio issue for the editor. Written as `* LOC:` comments after the event they land in, which
+// Avid and Resolve import as locators and timeline markers.
+
+use anyhow::{anyhow, Error};
+use serde::{
+    ser::{SerializeStruct, Serializer},
+    Serialize,
+};
+use vtc::Timecode;
+
+// the locator colors Avid and Resolve share
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MarkerColor {
+    Red,
+    Green,
+    Blue,
+    Cyan,
+    Magenta,
+    Yellow,
+    Black,
+    White,
+}
+
+impl MarkerColor {
+    pub const ALL: [MarkerColor; 8] = [
+        MarkerColor::Red,
+        MarkerColor::Green,
+        MarkerColor::Blue,
+        MarkerColor::Cyan,
+        MarkerColor::Magenta,
+        MarkerColor::Yellow,
+        MarkerColor::Black,
+        MarkerColor::White,
+    ];
+}
+
+impl From<MarkerColor> for &str {
+    fn from(value: MarkerColor) -> Self {
+        match value {
+            MarkerColor::Red => "RED",
+            MarkerColor::Green => "GREEN",
+            MarkerColor::Blue => "BLUE",
+            MarkerColor::Cyan => "CYAN",
+            MarkerColor::Magenta => "MAGENTA",
+            MarkerColor::Yellow => "YELLOW",
+            MarkerColor::Black => "BLACK",
+            MarkerColor::White => "WHITE",
+        }
+    }
+}
+
+impl TryFrom<&str> for MarkerColor {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        MarkerColor::ALL
+            .into_iter()
+            .find(|color| <&str>::from(*color).eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| anyhow!("Invalid marker color '{}'", value))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Marker {
+    // where the marker lands on the record timeline
+    pub record: Timecode,
+    pub color: MarkerColor,
+    pub name: String,
+    pub note: Option<String>,
+    pub duration_frames: Option<u32>,
+}
+
+impl Marker {
+    // `* LOC: 01:00:05:00 RED     great moment`, with any note following the name. Line breaks
+    // are flattened as they'd end the comment.
+    pub fn loc_line(&self) -> String {
+        let comment = match self.note.as_deref() {
+            Some(note) => format!("{} - {}", self.name, note),
+            None => self.name.clone(),
+        };
+        format!(
+            "* LOC: {} {:<7} {}",
+            self.record.timecode(),
+            <&str>::from(self.color),
+            comment.split_whitespace().collect::<Vec<_>>().join(" ")
+        )
+    }
+
+    pub fn record_out(&self) -> Timecode {
+        self.duration_frames
+            .and_then(|frames| {
+                Timecode::with_frames(self.record.frames() + frames as i64, self.record.rate()).ok()
+            })
+            .unwrap_or(self.record)
+    }
+}
+
+impl Serialize for Marker {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Marker", 5)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("color", <&str>::from(self.color))?;
+        state.serialize_field("note", &self.note)?;
+        state.serialize_field("record", &self.record.timecode())?;
+        state.serialize_field("duration_frames", &self.duration_frames)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use vtc::rates;
+
+    #[test]
+    fn loc_lines() {
+        let marker = Marker {
+            record: Timecode::with_frames("01:00:05:12", rates::F24).unwrap(),
+            color: "magenta".try_into().unwrap(),
+            name: "great\nmoment".into(),
+            note: None,
+            duration_frames: Some(48),
+        };
+        assert_eq!(marker.loc_line(), "* LOC: 01:00:05:12 MAGENTA great moment");
+        assert_eq!(marker.record_out().timecode(), "01:00:07:12");
+
+        let marker = Marker {
+            color: MarkerColor::Red,
+            name: "audio issue".into(),
+            note: Some("mic 2 crackles".into()),
+            ..marker
+        };
+        assert_eq!(
+            marker.loc_line(),
+            "* LOC: 01:00:05:12 RED     audio issue - mic 2 crackles"
+        );
+        assert!(MarkerColor::try_from("pink").is_err());
+    }
+}
@@ -0,0 +1,165 @@
+// Time-of-day LTC on overnight broadcasts wraps from 23:59:59:xx to 00:00:00:00. Edits are queued
+// on a timeline counting on past midnight, so in and out points and durations stay in order, and
+// timecodes are brought back into the day as they're written. A record timeline on the LTC can
+// instead be offset to start in hour 01, so it runs on past midnight without wrapping.
+
+use vtc::{Framerate, Ntsc, Timecode};
+
+use crate::edl_writer::{edit_queue::OrderedEdit, Event, Marker, Midnight, RecordTimecode};
+
+#[derive(Debug)]
+pub struct DayTimecodes {
+    // only a record timeline on the LTC is offset, a continuous one starts where it's set to
+    offset_record: bool,
+    // whole hours taken off the record timeline, from the first edit
+    record_offset: Option<i64>,
+}
+
+impl DayTimecodes {
+    pub fn new(midnight: Midnight, record_timecode: RecordTimecode) -> Self {
+        DayTimecodes {
+            offset_record: midnight == Midnight::OffsetRecord
+                && record_timecode == RecordTimecode::Ltc,
+            record_offset: None,
+        }
+    }
+
+    pub fn start(&mut self, record_start: Timecode) {
+        if self.offset_record {
+            let hour = hour_frames(record_start.rate());
+            self.record_offset
+                .get_or_insert((record_start.frames().div_euclid(hour) - 1) * hour);
+        }
+    }
+
+    // the event as it's written to the EDL
+    pub fn apply(&self, event: &Event) -> Event {
+        let mut event = event.clone();
+        for clip in event.clips_mut() {
+            clip.source_in = wrap(clip.source_in);
+            clip.source_out = wrap(clip.source_out);
+            clip.record_in = self.record(clip.record_in);
+            clip.record_out = self.record(clip.record_out);
+        }
+        event
+    }
+
+    pub fn apply_marker(&self, marker: &Marker) -> Marker {
+        Marker {
+            record: self.record(marker.record),
+            ..marker.clone()
+        }
+    }
+
+    pub fn apply_open(&self, edit: &OrderedEdit) -> OrderedEdit {
+        OrderedEdit {
+            timecode: wrap(edit.timecode),
+            record: self.record(edit.record),
+            ..edit.clone()
+        }
+    }
+
+    fn record(&self, record: Timecode) -> Timecode {
+        match self.record_offset {
+            Some(offset) => Timecode::with_frames(record.frames() - offset, record.rate())
+                .map(wrap)
+                .unwrap_or(record),
+            None => wrap(record),
+        }
+    }
+}
+
+// the timecode brought back into the 24 hours of the day
+pub fn wrap(tc: Timecode) -> Timecode {
+    let day = day_frames(tc.rate());
+    Timecode::with_frames(tc.frames().rem_euclid(day), tc.rate()).unwrap_or(tc)
+}
+
+// the LTC frame on the day closest to `near`, on the timeline counting on past midnight. LTC
+// running back less than half a day is a jump, further is a wrap through midnight.
+pub fn unwrap(tc: Timecode, near: Timecode) -> Timecode {
+    let day = day_frames(tc.rate());
+    let days = (near.frames() - tc.frames() + day / 2).div_euclid(day);
+    Timecode::with_frames(tc.frames() + days * day, tc.rate()).unwrap_or(tc)
+}
+
+// drop frame skips two frame labels a minute at 29.97, four at 59.94, except every tenth minute.
+// Each hour holds the same frames either way.
+pub fn day_frames(rate: Framerate) -> i64 {
+    hour_frames(rate) * 24
+}
+
+fn hour_frames(rate: Framerate) -> i64 {
+    let timebase = rate.timebase().to_integer();
+    match rate.ntsc() {
+        Ntsc::DropFrame => timebase * 3600 - timebase / 15 * 54,
+        _ => timebase * 3600,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        edl_writer::{AVChannels, Clip},
+        export::test::clip,
+    };
+    use vtc::rates;
+
+    #[test]
+    fn wrap_through_midnight() {
+        let tc = |tc: &str| Timecode::with_frames(tc, rates::F24).unwrap();
+        let unwrapped = unwrap(tc("00:00:02:00"), tc("23:59:58:00"));
+        assert_eq!(unwrapped.frames() - tc("23:59:58:00").frames(), 96);
+        assert_eq!(wrap(unwrapped), tc("00:00:02:00"));
+        // counting on from past midnight, and LTC jumping back a little
+        assert_eq!(
+            unwrap(tc("00:00:04:00"), unwrapped).frames(),
+            unwrapped.frames() + 48
+        );
+        assert_eq!(unwrap(tc("23:59:59:00"), unwrapped), tc("23:59:59:00"));
+        assert_eq!(
+            unwrap(tc("10:00:00:00"), tc("10:00:05:00")),
+            tc("10:00:00:00")
+        );
+
+        let df = |tc: &str| Timecode::with_frames(tc, rates::F29_97_DF).unwrap();
+        let unwrapped = unwrap(df("00:00:00;00"), df("23:59:59;29"));
+        assert_eq!(unwrapped.frames() - df("23:59:59;29").frames(), 1);
+
+        // an edit across midnight written on the LTC, or on a record timeline from hour 01
+        let event = Event::Cut(Clip {
+            record_out: unwrapped,
+            source_out: unwrapped,
+            ..clip(
+                1,
+                Some("a"),
+                AVChannels::video_only(),
+                ("23:30:00;00", "23:30:00;00"),
+                rates::F29_97_DF,
+            )
+        });
+        let written = |midnight: Midnight| {
+            let mut day_tcs = DayTimecodes::new(midnight, RecordTimecode::Ltc);
+            day_tcs.start(df("23:30:00;00"));
+            let event = day_tcs.apply(&event);
+            let clip: &Clip = (&event).into();
+            [
+                clip.source_in,
+                clip.source_out,
+                clip.record_in,
+                clip.record_out,
+            ]
+            .map(|tc| tc.timecode())
+            .join(" ")
+        };
+        assert_eq!(
+            written(Midnight::Wrap),
+            "23:30:00;00 00:00:00;00 23:30:00;00 00:00:00;00"
+        );
+        assert_eq!(
+            written(Midnight::OffsetRecord),
+            "23:30:00;00 00:00:00;00 01:30:00;00 02:00:00;00"
+        );
+    }
+}
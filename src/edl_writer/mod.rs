@@ -4,9 +4,17 @@
 // https://www.niwa.nu/2013/05/how-to-read-an-edl/
 // https://opentimelineio.readthedocs.io/en/latest/api/python/opentimelineio.adapters.cmx_3600.html
 
+pub mod correction;
+pub mod dialect;
 pub mod edit_queue;
-
-use anyhow::{anyhow, Context, Error};
+pub mod marker;
+pub mod midnight;
+pub mod parser;
+pub mod reel;
+pub mod sidecar;
+pub mod source_tc;
+
+use anyhow::{anyhow, bail, Context, Error};
 use serde::{
     ser::{SerializeStruct, Serializer},
     Deserialize, Serialize,
@@ -14,37 +22,109 @@ use serde::{
 use vtc::Timecode;
 
 use std::{
-    cmp::Ordering,
-    fs::File,
+    fs::{self, File, OpenOptions},
     io::{BufWriter, ErrorKind, Write},
-    path::Path,
+    iter,
+    path::{Path, PathBuf},
 };
 
-use crate::edl_writer::edit_queue::{Edit, OrderedEdit};
-use edit_queue::EditQueue;
+use crate::{
+    edl_writer::edit_queue::{Edit, Motion, OrderedEdit},
+    export::{ExportFormat, ExportOpt, Session},
+    ltc_decoder::{framerate, TCError},
+    state::Opt,
+};
+use correction::EditPatch;
+use dialect::Dialect;
+use edit_queue::{Consolidation, EditQueue, Merge};
+use marker::{Marker, MarkerColor};
+use midnight::DayTimecodes;
+use reel::Reels;
+use sidecar::{Correction, EventOrigin, Sidecar};
+use source_tc::{SourceTc, SourceTimecodes};
+
+// the track edits are on unless they name another
+pub const MAIN_TRACK: &str = "V1";
 
 #[derive(Debug)]
 pub struct Edl {
-    file: BufWriter<File>,
-    edit_queue: EditQueue,
+    title: String,
+    ntsc: Ntsc,
+    dialect: Dialect,
+    rollover_edits: usize,
+    reels: Reels,
+    source_timecodes: SourceTimecodes,
+    export_opt: ExportOpt,
+    export_formats: Vec<ExportFormat>,
+    pause_gap: PauseGap,
+    handles: Handles,
+    day_timecodes: DayTimecodes,
+    sidecar: Option<Sidecar>,
+    // the main track first. Other tracks, ie. a graphics layer over the program, are added by
+    // their first edit and written to an EDL of their own.
+    tracks: Vec<Track>,
 }
 
 impl Edl {
-    pub fn new(dir: &Path, title: &str, ntsc: Ntsc) -> Result<Self, Error> {
+    pub fn new(opt: &Opt) -> Result<Self, Error> {
+        let record_start = match opt.record_timecode {
+            RecordTimecode::Ltc => None,
+            RecordTimecode::Continuous => Some(
+                Timecode::with_frames(opt.record_start.trim(), framerate(opt)?).map_err(|e| {
+                    anyhow!(
+                        "Invalid record start timecode '{}': {}",
+                        opt.record_start,
+                        e.into_msg()
+                    )
+                })?,
+            ),
+        };
+        let (file, path) = Edl::init_file(&opt.dir, &opt.title, opt.ntsc)?;
+        // a missing sidecar shouldn't stop the EDL from being recorded
+        let sidecar = Sidecar::new(&path)
+            .inspect_err(|e| log::error!("{:#}", e))
+            .ok();
         Ok(Edl {
-            file: Edl::init_file(dir, title, ntsc)?,
-            edit_queue: EditQueue::default(),
+            title: opt.title.clone(),
+            ntsc: opt.ntsc,
+            dialect: opt.edl_dialect,
+            rollover_edits: opt.rollover_edits,
+            reels: Reels::new(&opt.reel_ids, opt.edl_dialect.reel_width())?,
+            source_timecodes: SourceTimecodes::new(&opt.source_timecodes.lock(), framerate(opt)?),
+            export_opt: opt.into(),
+            export_formats: opt.export_formats.clone(),
+            pause_gap: opt.pause_gap,
+            handles: Handles::new(opt)?,
+            day_timecodes: DayTimecodes::new(opt.midnight, opt.record_timecode),
+            sidecar,
+            tracks: vec![Track {
+                name: MAIN_TRACK.into(),
+                title: opt.title.clone(),
+                file,
+                rollover: Rollover::new(&path, opt.rollover_edits, opt.edl_dialect),
+                path,
+                events: Vec::new(),
+                edit_queue: EditQueue::with_record_start(record_start).with_consolidation(
+                    Consolidation {
+                        min_edit_frames: opt.min_edit_frames,
+                        merge_same_source: opt.merge_same_source,
+                    },
+                ),
+                markers: Vec::new(),
+                written_markers: Vec::new(),
+            }],
         })
     }
 
-    fn init_file(dir: &Path, title: &str, ntsc: Ntsc) -> Result<BufWriter<File>, Error> {
-        let mut file = BufWriter::new(Edl::numbered_file(dir, title)?);
+    fn init_file(dir: &Path, title: &str, ntsc: Ntsc) -> Result<(BufWriter<File>, PathBuf), Error> {
+        let (file, path) = Edl::numbered_file(dir, title)?;
+        let mut file = BufWriter::new(file);
         file.write_all(format!("TITLE: {}\nFCM: {}", title, <&str>::from(ntsc)).as_bytes())?;
         file.flush()?;
-        Ok(file)
+        Ok((file, path))
     }
 
-    fn numbered_file(dir: &Path, title: &str) -> Result<File, Error> {
+    fn numbered_file(dir: &Path, title: &str) -> Result<(File, PathBuf), Error> {
         let mut dir = dir.to_path_buf();
         let mut file_name = format!("{}.edl", title);
         let mut num_buffer = itoa::Buffer::new();
@@ -62,35 +142,562 @@ impl Edl {
                         }
                         None
                     }
-                    r @ _ => Some(r),
+                    r => Some(r.map(|file| (file, dir.clone()))),
                 }
             })
             .unwrap()
             .context("Could not create EDL file")
     }
 
-    pub fn write_event(&mut self, event: Event) -> Result<Event, Error> {
-        let event_str: String = (&event).try_into()?;
-        self.file.write_all(format!("\n{event_str}").as_bytes())?;
-        self.file.flush()?;
+    // writes the event to the current part of the track's EDL and returns it, renumbered for that
+    // part, along with the name of the file it landed in.
+    pub fn write_event(
+        &mut self,
+        track: Option<&str>,
+        mut event: Event,
+        origin: &EventOrigin,
+    ) -> Result<(Event, String), Error> {
+        let index = self.track_index(track)?;
+        let track = &mut self.tracks[index];
+        let edit_number = <&Clip>::from(&event).edit_number;
+        if edit_number - track.rollover.edit_offset > track.rollover.max_edits {
+            track.roll_over(edit_number - 1, self.ntsc)?;
+        }
+        event.set_edit_number(edit_number - track.rollover.edit_offset);
+        // the track keeps the event on the timeline counting past midnight
+        let written = self.day_timecodes.apply(&event);
+        let mut event_str = self.dialect.format_event(&written, &mut self.reels)?;
+        for marker in track.markers.drain(..) {
+            let loc_line = self.day_timecodes.apply_marker(&marker).loc_line();
+            event_str.push_str(&format!("\n{loc_line}"));
+            track.written_markers.push(marker);
+        }
+        track.file.write_all(format!("\n{event_str}").as_bytes())?;
+        track.file.flush()?;
+        let file_name = track.rollover.file_name();
+        if let Some(sidecar) = self.sidecar.as_mut() {
+            // the edit that closed this event holds the frame the request resolved to
+            let ltc_frame = track
+                .edit_queue
+                .front()
+                .map(|edit| midnight::wrap(edit.timecode));
+            sidecar
+                .append(&written, &track.name, &file_name, origin, ltc_frame)
+                .unwrap_or_else(|e| log::error!("Could not write to sidecar: {:#}", e));
+        }
+        track.events.push(event);
         log::info!("{event_str}");
-        Ok(event)
+        Ok((written, file_name))
+    }
+
+    // writes each selected timeline export next to each track's EDL file, named after it.
+    pub fn write_exports(&self) -> Result<(), Error> {
+        self.tracks.iter().try_for_each(|track| {
+            let mut export_opt = self.export_opt.clone();
+            for event in &track.events {
+                if let SourceTape::AX(tape) = &<&Clip>::from(event).source_tape {
+                    if export_opt.media_start(tape).is_none() {
+                        if let Some(start) = self.source_timecodes.media_start(tape) {
+                            export_opt.media_starts.push((tape.clone(), start));
+                        }
+                    }
+                }
+            }
+            let session = Session::new(&track.title, &track.events, &export_opt);
+            self.export_formats
+                .iter()
+                .try_for_each(|format| format.write(&session, &track.path))
+        })
+    }
+
+    pub fn push_edit(&mut self, track: Option<&str>, edit: Edit) -> Result<(), Error> {
+        let index = self.edit_track(track, &edit)?;
+        self.tracks[index].edit_queue.push(edit)
+    }
+
+    // pushes an edit logged during the recording, which is folded into the open edit instead
+    // when it's a double-tap
+    pub fn log_edit(&mut self, track: Option<&str>, edit: Edit) -> Result<Option<Merge>, Error> {
+        let index = self.edit_track(track, &edit)?;
+        self.tracks[index].edit_queue.push_consolidated(edit)
+    }
+
+    fn edit_track(&mut self, track: Option<&str>, edit: &Edit) -> Result<usize, Error> {
+        self.source_timecodes.start(edit.timecode);
+        self.day_timecodes.start(edit.timecode);
+        match self.track_index(track) {
+            Ok(index) => Ok(index),
+            Err(_) => self.add_track(track_name(track)?, edit.timecode),
+        }
+    }
+
+    // cuts every other track to black where the recording ends on `track`, closing the edit left
+    // open on each
+    pub fn end_tracks(
+        &mut self,
+        track: Option<&str>,
+        origin: &EventOrigin,
+    ) -> Result<Vec<(Event, String)>, Error> {
+        let index = self.track_index(track)?;
+        let timecode = self.tracks[index]
+            .edit_queue
+            .front()
+            .context("No current value in frame_queue")?
+            .timecode;
+        let others: Vec<String> = self
+            .tracks
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, track)| track.name.clone())
+            .collect();
+        let mut written = Vec::new();
+        for other in others {
+            self.push_edit(Some(&other), Edit::black(timecode))?;
+            for event in self.try_build_events(Some(&other))? {
+                written.push(self.write_event(Some(&other), event, origin)?);
+            }
+        }
+        Ok(written)
     }
 
-    pub fn push_edit(&mut self, edit: Edit) -> Result<(), Error> {
-        self.edit_queue.push(edit)
+    // places a marker at the LTC frame on the main track's record timeline. It's logged to the
+    // sidecar straight away, and to the EDL once the edit it lands in is written.
+    pub fn add_marker(
+        &mut self,
+        ltc_frame: Timecode,
+        color: MarkerColor,
+        name: String,
+        note: Option<String>,
+        duration_frames: Option<u32>,
+        origin: &EventOrigin,
+    ) -> Result<Marker, Error> {
+        let track = &mut self.tracks[0];
+        let marker = Marker {
+            record: track.edit_queue.record_at(ltc_frame)?,
+            color,
+            name,
+            note,
+            duration_frames,
+        };
+        let written = self.day_timecodes.apply_marker(&marker);
+        if let Some(sidecar) = self.sidecar.as_mut() {
+            // logged against the part being written, the next event may still roll it over
+            let file_name = track.rollover.file_name();
+            sidecar
+                .append_marker(&written, &track.name, &file_name, origin, ltc_frame)
+                .unwrap_or_else(|e| log::error!("Could not write to sidecar: {:#}", e));
+        }
+        track.markers.push(marker);
+        Ok(written)
     }
 
-    pub fn try_build_event(&mut self) -> Result<Event, Error> {
-        let prev_edit = self
+    // removes edit `n` of the track's recording, or its last edit, and rewrites its EDL. The edit
+    // before it plays on over the gap. Returns the removed event along with the events from the
+    // one before it on, as rewritten.
+    pub fn delete_event(
+        &mut self,
+        track: Option<&str>,
+        n: Option<usize>,
+        origin: &EventOrigin,
+    ) -> Result<(Event, Vec<(Event, String)>), Error> {
+        let index = self.track_index(track)?;
+        let track = &mut self.tracks[index];
+        let i = Edl::event_index(track, n)?;
+        let last = i + 1 == track.events.len();
+        let removed_file = track.part_file_name(i);
+        let removed = correction::delete(&mut track.events, i)?;
+        if last {
+            track.edit_queue.relink(&track.events);
+        }
+        track.rewrite(
+            self.ntsc,
+            self.dialect,
+            &mut self.reels,
+            &self.day_timecodes,
+        )?;
+        let removed = self.day_timecodes.apply(&removed);
+        let rewritten = Edl::rewritten(track, i.saturating_sub(1), &self.day_timecodes);
+        if let Some(sidecar) = self.sidecar.as_mut() {
+            iter::once((Correction::Deleted, &removed, &removed_file))
+                .chain(
+                    rewritten
+                        .iter()
+                        .map(|(event, file)| (Correction::Rewritten, event, file)),
+                )
+                .try_for_each(|(correction, event, file)| {
+                    sidecar.append_correction(correction, event, &track.name, file, origin)
+                })
+                .unwrap_or_else(|e| log::error!("Could not write to sidecar: {:#}", e));
+        }
+        Ok((removed, rewritten))
+    }
+
+    // changes the source, channels or transition of edit `n` of the track's recording, or its
+    // last edit, and rewrites its EDL. Returns the events from the patched one on.
+    pub fn patch_event(
+        &mut self,
+        track: Option<&str>,
+        n: Option<usize>,
+        edit_patch: &EditPatch,
+        origin: &EventOrigin,
+    ) -> Result<Vec<(Event, String)>, Error> {
+        let index = self.track_index(track)?;
+        let track = &mut self.tracks[index];
+        let i = Edl::event_index(track, n)?;
+        correction::patch(&mut track.events, i, edit_patch)?;
+        if i + 1 == track.events.len() {
+            track.edit_queue.relink(&track.events);
+        }
+        track.rewrite(
+            self.ntsc,
+            self.dialect,
+            &mut self.reels,
+            &self.day_timecodes,
+        )?;
+        let rewritten = Edl::rewritten(track, i, &self.day_timecodes);
+        if let Some(sidecar) = self.sidecar.as_mut() {
+            rewritten
+                .iter()
+                .enumerate()
+                .try_for_each(|(n, (event, file))| {
+                    let correction = match n {
+                        0 => Correction::Patched,
+                        _ => Correction::Rewritten,
+                    };
+                    sidecar.append_correction(correction, event, &track.name, file, origin)
+                })
+                .unwrap_or_else(|e| log::error!("Could not write to sidecar: {:#}", e));
+        }
+        Ok(rewritten)
+    }
+
+    // the edits on the track waiting for their out point, as they'd be written by the next edit
+    pub fn open_edits(&self, track: Option<&str>) -> Result<Vec<OrderedEdit>, Error> {
+        let index = self.track_index(track)?;
+        Ok(self.tracks[index]
             .edit_queue
-            .pop_front()
-            .context("No previous value in frame_queue")?;
-        let curr_edit = self
+            .open()
+            .into_iter()
+            .map(|edit| self.day_timecodes.apply_open(edit))
+            .collect())
+    }
+
+    // corrects the edit the last edit on the track opened, before the next edit writes it
+    pub fn amend_open_edit(
+        &mut self,
+        track: Option<&str>,
+        edit_patch: &EditPatch,
+    ) -> Result<Vec<OrderedEdit>, Error> {
+        let index = self.track_index(track)?;
+        Ok(self.tracks[index]
             .edit_queue
+            .amend_open(edit_patch)?
+            .into_iter()
+            .map(|edit| self.day_timecodes.apply_open(edit))
+            .collect())
+    }
+
+    pub fn has_track(&self, track: Option<&str>) -> bool {
+        self.track_index(track).is_ok()
+    }
+
+    // whether the track has written edit `n`, or any edit
+    pub fn has_event(&self, track: Option<&str>, n: Option<usize>) -> bool {
+        self.track_index(track)
+            .and_then(|index| Edl::event_index(&self.tracks[index], n))
+            .is_ok()
+    }
+
+    // edits are numbered from 1 across every part of the track
+    fn event_index(track: &Track, n: Option<usize>) -> Result<usize, Error> {
+        match n {
+            None => track.events.len().checked_sub(1),
+            Some(n) => n.checked_sub(1).filter(|i| *i < track.events.len()),
+        }
+        .with_context(|| match n {
+            Some(n) => format!("Edit {} has not been written on track {}", n, track.name),
+            None => format!("No edits have been written on track {}", track.name),
+        })
+    }
+
+    fn rewritten(track: &Track, from: usize, day_timecodes: &DayTimecodes) -> Vec<(Event, String)> {
+        track
+            .events
+            .iter()
+            .enumerate()
+            .skip(from)
+            .map(|(i, event)| (day_timecodes.apply(event), track.part_file_name(i)))
+            .collect()
+    }
+
+    // picks a paused recording back up. Each track was cut to black when it paused, and that black
+    // is closed by the track's next edit unless the gap is left out. Returns the tracks as they
+    // were paused, to go back to if the edit resuming the recording can't be logged.
+    pub fn resume(&mut self) -> Paused {
+        let paused = Paused(
+            self.tracks
+                .iter()
+                .map(|track| track.edit_queue.clone())
+                .collect(),
+        );
+        if self.pause_gap == PauseGap::LeaveOut {
+            for track in &mut self.tracks {
+                track.edit_queue.leave_out_gap();
+            }
+        }
+        paused
+    }
+
+    pub fn stay_paused(&mut self, paused: Paused) {
+        for (track, edit_queue) in self.tracks.iter_mut().zip(paused.0) {
+            track.edit_queue = edit_queue;
+        }
+    }
+
+    pub fn set_source_timecode(&mut self, tape: &str, source_tc: Option<SourceTc>) {
+        self.source_timecodes.set(tape, source_tc);
+    }
+
+    // builds an event from each edit the last pushed edit on the track closed. That's one for
+    // most edits, none for an audio split over a video only source, and two when an edit brings
+    // split video and audio back together.
+    pub fn try_build_events(&mut self, track: Option<&str>) -> Result<Vec<Event>, Error> {
+        let index = self.track_index(track)?;
+        let edit_queue = &mut self.tracks[index].edit_queue;
+        let curr_edit = edit_queue
             .front()
-            .context("No current value in frame_queue")?;
-        OrderedEditInOutPair::new(&prev_edit, curr_edit).try_into()
+            .context("No current value in frame_queue")?
+            .clone();
+        let mut events = Vec::new();
+        while let Some(prev_edit) = edit_queue.pop_closed() {
+            let mut event = OrderedEditInOutPair::new(&prev_edit, &curr_edit)
+                .with_handles(self.handles)
+                .try_into()?;
+            self.source_timecodes.apply(&mut event)?;
+            events.push(event);
+        }
+        Ok(events)
+    }
+
+    fn track_index(&self, track: Option<&str>) -> Result<usize, Error> {
+        let name = track_name(track)?;
+        self.tracks
+            .iter()
+            .position(|track| track.name == name)
+            .with_context(|| format!("Track {name} has no edits"))
+    }
+
+    // a track written next to the main track's EDL, ie. `{title}_V2.edl`. It joins the main
+    // track's record timeline at its first edit.
+    fn add_track(&mut self, name: String, timecode: Timecode) -> Result<usize, Error> {
+        let main = &self.tracks[0];
+        let dir = main.path.parent().unwrap_or_else(|| Path::new("./"));
+        let stem = main
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.title.clone());
+        let title = format!("{stem}_{name}");
+        let (file, path) = Edl::init_file(dir, &title, self.ntsc)?;
+        let edit_queue = main.edit_queue.layer(timecode)?;
+        log::info!("Recording track {name} to {}", path.display());
+        self.tracks.push(Track {
+            name,
+            title,
+            file,
+            rollover: Rollover::new(&path, self.rollover_edits, self.dialect),
+            path,
+            events: Vec::new(),
+            edit_queue,
+            markers: Vec::new(),
+            written_markers: Vec::new(),
+        });
+        Ok(self.tracks.len() - 1)
+    }
+}
+
+// `V1` unless named. Track names are kept to letters and numbers as they go into file names.
+pub fn track_name(track: Option<&str>) -> Result<String, Error> {
+    match track.map(str::trim) {
+        None | Some("") => Ok(MAIN_TRACK.into()),
+        Some(track) if track.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            Ok(track.to_ascii_uppercase())
+        }
+        Some(track) => Err(anyhow!(
+            "Invalid track '{}', track names can only hold letters and numbers",
+            track
+        )),
+    }
+}
+
+// the edit queue of each track when the recording was resumed
+#[derive(Debug)]
+pub struct Paused(Vec<EditQueue>);
+
+// one layer of the recording, with its own edits and EDL file
+#[derive(Debug)]
+struct Track {
+    name: String,
+    title: String,
+    file: BufWriter<File>,
+    path: PathBuf,
+    rollover: Rollover,
+    events: Vec<Event>,
+    edit_queue: EditQueue,
+    // markers dropped since the last event was written, written after the next as they land in it
+    markers: Vec<Marker>,
+    // markers already in the EDL, kept to write them again when it's rewritten
+    written_markers: Vec<Marker>,
+}
+
+impl Track {
+    // the name of the part the track's `i`th event is written to
+    fn part_file_name(&self, i: usize) -> String {
+        self.rollover
+            .parts
+            .get(i / self.rollover.max_edits)
+            .and_then(|part| part.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    // continues the EDL in a new `{title}_part{n}.edl` once the current part is full. Numbering
+    // restarts after `edit_offset`, the open edit stays queued and lands in the new part.
+    fn roll_over(&mut self, edit_offset: usize, ntsc: Ntsc) -> Result<(), Error> {
+        let part = self.rollover.part + 1;
+        let dir = self.path.parent().unwrap_or_else(|| Path::new("./"));
+        let stem = self
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.title.clone());
+        let (file, path) = Edl::numbered_file(dir, &format!("{stem}_part{part}"))?;
+        let mut file = BufWriter::new(file);
+        file.write_all(
+            format!(
+                "TITLE: {} PART {}\nFCM: {}",
+                self.title,
+                part,
+                <&str>::from(ntsc)
+            )
+            .as_bytes(),
+        )?;
+        file.flush()?;
+
+        self.file = file;
+        self.rollover.part = part;
+        self.rollover.parts.push(path.clone());
+        self.rollover.path = path;
+        self.rollover.edit_offset = edit_offset;
+        log::info!(
+            "EDL reached {} edits. Continuing in {}",
+            self.rollover.max_edits,
+            self.rollover.file_name()
+        );
+        Ok(())
+    }
+
+    // writes the track's events out again once one has been corrected, renumbered across its
+    // parts. Each part is written to a temporary file first and swapped in, so an EDL is never
+    // left half written. Parts left empty are removed and writing carries on in the last.
+    fn rewrite(
+        &mut self,
+        ntsc: Ntsc,
+        dialect: Dialect,
+        reels: &mut Reels,
+        day_timecodes: &DayTimecodes,
+    ) -> Result<(), Error> {
+        self.file.flush()?;
+        let max_edits = self.rollover.max_edits;
+        for (i, event) in self.events.iter_mut().enumerate() {
+            event.set_edit_number(i % max_edits + 1);
+        }
+        // each marker follows the last event starting at or before it
+        let mut markers: Vec<Vec<&Marker>> = vec![Vec::new(); self.events.len()];
+        for marker in &self.written_markers {
+            let index = self
+                .events
+                .iter()
+                .rposition(|event| {
+                    correction::incoming(event).record_in.frames() <= marker.record.frames()
+                })
+                .unwrap_or_default();
+            if let Some(markers) = markers.get_mut(index) {
+                markers.push(marker);
+            }
+        }
+
+        let part_count = self.events.len().div_ceil(max_edits).max(1);
+        for part in 1..=part_count {
+            let path = self
+                .rollover
+                .parts
+                .get(part - 1)
+                .context("No EDL part to rewrite")?;
+            let title = match part {
+                1 => self.title.clone(),
+                _ => format!("{} PART {}", self.title, part),
+            };
+            let mut edl = format!("TITLE: {}\nFCM: {}", title, <&str>::from(ntsc));
+            let start = (part - 1) * max_edits;
+            let end = (start + max_edits).min(self.events.len());
+            for (event, markers) in self.events[start..end].iter().zip(&markers[start..end]) {
+                let event = day_timecodes.apply(event);
+                edl.push_str(&format!("\n{}", dialect.format_event(&event, reels)?));
+                for marker in markers {
+                    let marker = day_timecodes.apply_marker(marker);
+                    edl.push_str(&format!("\n{}", marker.loc_line()));
+                }
+            }
+            let tmp = path.with_extension("edl.tmp");
+            fs::write(&tmp, edl).context("Could not write EDL")?;
+            fs::rename(&tmp, path).context("Could not replace EDL")?;
+        }
+        for path in self.rollover.parts.drain(part_count..) {
+            fs::remove_file(&path)
+                .unwrap_or_else(|e| log::error!("Could not remove {}: {}", path.display(), e));
+        }
+
+        let path = self.rollover.parts[part_count - 1].clone();
+        self.file = BufWriter::new(OpenOptions::new().append(true).open(&path)?);
+        self.rollover.part = part_count;
+        self.rollover.path = path;
+        self.rollover.edit_offset = (part_count - 1) * max_edits;
+        self.edit_queue.renumber(self.events.len());
+        Ok(())
+    }
+}
+
+// tracks which part of the EDL is being written once a long recording outgrows one file
+#[derive(Debug)]
+struct Rollover {
+    part: usize,
+    path: PathBuf,
+    // every part written so far, the first being the track's EDL
+    parts: Vec<PathBuf>,
+    edit_offset: usize,
+    max_edits: usize,
+}
+
+impl Rollover {
+    fn new(path: &Path, max_edits: usize, dialect: Dialect) -> Self {
+        // never let a part grow past what the dialect can number
+        let dialect_max = 10_usize.pow(dialect.edit_number_digits() as u32) - 1;
+        Rollover {
+            part: 1,
+            path: path.to_path_buf(),
+            parts: vec![path.to_path_buf()],
+            edit_offset: 0,
+            max_edits: max_edits.clamp(1, dialect_max),
+        }
+    }
+
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
     }
 }
 
@@ -135,32 +742,235 @@ impl Ntsc {
     }
 }
 
+// where record in and out points come from. Continuous record starts at a set timecode and runs
+// on by each edit's duration, so the EDL conforms without gaps whatever the LTC does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordTimecode {
+    Ltc,
+    Continuous,
+}
+
+impl From<RecordTimecode> for &str {
+    fn from(value: RecordTimecode) -> Self {
+        match value {
+            RecordTimecode::Ltc => "LTC",
+            RecordTimecode::Continuous => "Continuous",
+        }
+    }
+}
+
+impl From<RecordTimecode> for String {
+    fn from(value: RecordTimecode) -> Self {
+        <&str>::from(value).into()
+    }
+}
+
+impl TryFrom<&str> for RecordTimecode {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            x if x == <&str>::from(RecordTimecode::Ltc) => Ok(RecordTimecode::Ltc),
+            x if x == <&str>::from(RecordTimecode::Continuous) => Ok(RecordTimecode::Continuous),
+            _ => Err(anyhow!("Invalid conversion")),
+        }
+    }
+}
+
+// the unit source handles are set in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HandleUnit {
+    Frames,
+    Seconds,
+}
+
+impl From<HandleUnit> for &str {
+    fn from(value: HandleUnit) -> Self {
+        match value {
+            HandleUnit::Frames => "Frames",
+            HandleUnit::Seconds => "Seconds",
+        }
+    }
+}
+
+impl From<HandleUnit> for String {
+    fn from(value: HandleUnit) -> Self {
+        <&str>::from(value).into()
+    }
+}
+
+impl TryFrom<&str> for HandleUnit {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            x if x == <&str>::from(HandleUnit::Frames) => Ok(HandleUnit::Frames),
+            x if x == <&str>::from(HandleUnit::Seconds) => Ok(HandleUnit::Seconds),
+            _ => Err(anyhow!("Invalid conversion")),
+        }
+    }
+}
+
+// source frames added before and after each clip in the EDL so editors can trim live cuts.
+// Record in and out points are left exact.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Handles {
+    pub head: u32,
+    pub tail: u32,
+}
+
+impl Handles {
+    pub fn new(opt: &Opt) -> Result<Self, Error> {
+        let frames = match opt.handle_unit {
+            HandleUnit::Frames => 1,
+            HandleUnit::Seconds => framerate(opt)?.timebase().to_integer() as u32,
+        };
+        Ok(Handles {
+            head: opt.head_handle * frames,
+            tail: opt.tail_handle * frames,
+        })
+    }
+
+    // the handles of a clip. The tail is counted from the end of any transition out of the clip,
+    // as its source plays on under the transition. Black and clips played off speed are left as
+    // logged.
+    fn for_clip(&self, clip: &Clip, transition_out: Option<u32>) -> Self {
+        if clip.speed.is_some() || matches!(clip.source_tape, SourceTape::BL) {
+            return Handles::default();
+        }
+        Handles {
+            head: self.head,
+            tail: match self.tail {
+                0 => 0,
+                tail => tail + transition_out.unwrap_or_default(),
+            },
+        }
+    }
+
+    // the source in and out points of the clip with its handles, as written to the EDL
+    fn widen(&self, clip: &Clip) -> (Timecode, Timecode) {
+        let rate = clip.source_in.rate();
+        match (
+            Timecode::with_frames((clip.source_in.frames() - self.head as i64).max(0), rate),
+            Timecode::with_frames(clip.source_out.frames() + self.tail as i64, rate),
+        ) {
+            (Ok(source_in), Ok(source_out)) => (source_in, midnight::wrap(source_out)),
+            _ => (clip.source_in, clip.source_out),
+        }
+    }
+}
+
+// what's written for the time a recording was paused, ie. over a commercial break
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PauseGap {
+    // nothing, a continuous record timeline picks up where it paused
+    LeaveOut,
+    Black,
+}
+
+impl From<PauseGap> for &str {
+    fn from(value: PauseGap) -> Self {
+        match value {
+            PauseGap::LeaveOut => "Leave Out",
+            PauseGap::Black => "Fill With Black",
+        }
+    }
+}
+
+impl From<PauseGap> for String {
+    fn from(value: PauseGap) -> Self {
+        <&str>::from(value).into()
+    }
+}
+
+impl TryFrom<&str> for PauseGap {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            x if x == <&str>::from(PauseGap::LeaveOut) => Ok(PauseGap::LeaveOut),
+            x if x == <&str>::from(PauseGap::Black) => Ok(PauseGap::Black),
+            _ => Err(anyhow!("Invalid conversion")),
+        }
+    }
+}
+
+// how time-of-day LTC is written once it wraps through midnight. Source timecodes always wrap,
+// as that's what the recorders read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Midnight {
+    // record timecodes wrap from 23:59:59:xx to 00:00:00:00 with the LTC
+    Wrap,
+    // the record timeline on the LTC is moved back to start in hour 01
+    OffsetRecord,
+}
+
+impl From<Midnight> for &str {
+    fn from(value: Midnight) -> Self {
+        match value {
+            Midnight::Wrap => "Wrap Timecode",
+            Midnight::OffsetRecord => "Offset Record",
+        }
+    }
+}
+
+impl From<Midnight> for String {
+    fn from(value: Midnight) -> Self {
+        <&str>::from(value).into()
+    }
+}
+
+impl TryFrom<&str> for Midnight {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            x if x == <&str>::from(Midnight::Wrap) => Ok(Midnight::Wrap),
+            x if x == <&str>::from(Midnight::OffsetRecord) => Ok(Midnight::OffsetRecord),
+            _ => Err(anyhow!("Invalid conversion")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum EditType {
     Cut,
     Dissolve,
     Wipe,
+    Key,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "lowercase")]
 #[cfg_attr(test, derive(Deserialize))]
 pub enum Event {
     Cut(Clip),
     Dissolve(Dissolve),
     Wipe(Wipe),
+    Key(Key),
 }
 
 impl Event {
-    fn get_strs(&self) -> Result<(String, String), Error> {
-        let c = "C   ".into();
+    pub(crate) fn clips_mut(&mut self) -> Vec<&mut Clip> {
         match self {
-            Event::Cut(_) => Ok((c, "".into())),
-            Event::Dissolve(_) => Ok((c, "D   ".into())),
-            Event::Wipe(w) => {
-                let num_str = validate_num_size(w.wipe_number)
-                    .context("Wipe number above 999 not allowed")?;
-                Ok((c, format!("W{num_str}")))
+            Event::Cut(clip) => vec![clip],
+            Event::Dissolve(Dissolve { from, to, .. }) | Event::Wipe(Wipe { from, to, .. }) => {
+                vec![from, to]
+            }
+            Event::Key(Key {
+                background,
+                foreground,
+                ..
+            }) => vec![background, foreground],
+        }
+    }
+
+    fn set_edit_number(&mut self, edit_number: usize) {
+        match self {
+            Event::Cut(clip) => clip.edit_number = edit_number,
+            Event::Dissolve(Dissolve { from, to, .. }) | Event::Wipe(Wipe { from, to, .. }) => {
+                from.edit_number = edit_number;
+                to.edit_number = edit_number;
+            }
+            Event::Key(key) => {
+                key.background.edit_number = edit_number;
+                key.foreground.edit_number = edit_number;
             }
         }
     }
@@ -172,6 +982,18 @@ impl<'a> From<&'a Event> for &'a SourceTape {
             Event::Cut(clip) => &clip.source_tape,
             Event::Dissolve(dissolve) => &dissolve.to.source_tape,
             Event::Wipe(wipe) => &wipe.to.source_tape,
+            Event::Key(key) => &key.background.source_tape,
+        }
+    }
+}
+
+impl<'a> From<&'a Event> for &'a Clip {
+    fn from(edit: &'a Event) -> Self {
+        match edit {
+            Event::Cut(clip) => clip,
+            Event::Dissolve(dissolve) => &dissolve.to,
+            Event::Wipe(wipe) => &wipe.to,
+            Event::Key(key) => &key.background,
         }
     }
 }
@@ -182,6 +1004,18 @@ impl From<&Event> for AVChannels {
             Event::Cut(clip) => clip.av_channels,
             Event::Dissolve(dissolve) => dissolve.to.av_channels,
             Event::Wipe(wipe) => wipe.to.av_channels,
+            Event::Key(key) => key.background.av_channels,
+        }
+    }
+}
+
+impl From<&Event> for EditType {
+    fn from(edit: &Event) -> Self {
+        match edit {
+            Event::Cut(_) => EditType::Cut,
+            Event::Dissolve(_) => EditType::Dissolve,
+            Event::Wipe(_) => EditType::Wipe,
+            Event::Key(_) => EditType::Key,
         }
     }
 }
@@ -221,6 +1055,13 @@ impl<'a> TryFrom<OrderedEditInOutPair<'a>> for Event {
                     wipe_number: value.in_.wipe_num.unwrap_or(1),
                 }))
             }
+
+            EditType::Key => Ok(Event::Key(Key {
+                background: value.as_prev_clip_full(),
+                foreground: value.as_dest_clip(),
+                key_in_frames: value.in_.edit_duration_frames,
+                key_out_frames: value.in_.key_out_frames,
+            })),
         }
     }
 }
@@ -228,23 +1069,55 @@ impl<'a> TryFrom<OrderedEditInOutPair<'a>> for Event {
 pub struct OrderedEditInOutPair<'a> {
     in_: &'a OrderedEdit,
     out_: &'a OrderedEdit,
+    handles: Handles,
 }
 
 impl<'a> OrderedEditInOutPair<'a> {
     pub fn new(in_: &'a OrderedEdit, out_: &'a OrderedEdit) -> Self {
-        OrderedEditInOutPair { in_, out_ }
+        OrderedEditInOutPair {
+            in_,
+            out_,
+            handles: Handles::default(),
+        }
+    }
+
+    pub fn with_handles(self, handles: Handles) -> Self {
+        OrderedEditInOutPair { handles, ..self }
     }
 
     pub fn as_dest_clip(&self) -> Clip {
-        Clip {
+        let tc_out = self.tc_out_with_edit_duration_if_greater();
+        let record_out = self.record_out(tc_out);
+        let (source_in, source_out) = match self.in_.motion {
+            Some(motion) => self.motion_source(motion, record_out),
+            None => (self.in_.timecode, tc_out),
+        };
+        let mut clip = Clip {
             source_tape: self.in_.source_tape.as_deref().into(),
             edit_number: self.in_.edit_number,
             av_channels: self.in_.av_channels,
-            source_in: self.in_.timecode,
-            source_out: self.tc_out_with_edit_duration_if_greater(),
-            record_in: self.in_.timecode,
-            record_out: self.tc_out_with_edit_duration_if_greater(),
-        }
+            source_in,
+            source_out,
+            record_in: self.in_.record,
+            record_out,
+            speed: self.in_.motion.map(|motion| motion.speed),
+            handles: Handles::default(),
+        };
+        clip.handles = self.handles.for_clip(&clip, self.transition_out());
+        clip
+    }
+
+    // the source frames played over the record duration. In reverse they run back from where
+    // the motion starts.
+    fn motion_source(&self, motion: Motion, record_out: Timecode) -> (Timecode, Timecode) {
+        let record_frames = record_out.frames() - self.in_.record.frames();
+        let source_frames = (record_frames as f32 * motion.speed / 100.0).round() as i64;
+        let moved = Timecode::with_frames(
+            motion.source_in.frames() + source_frames,
+            motion.source_in.rate(),
+        )
+        .unwrap_or(motion.source_in);
+        (motion.source_in.min(moved), motion.source_in.max(moved))
     }
 
     pub fn as_prev_clip_flat(&self) -> Clip {
@@ -254,11 +1127,52 @@ impl<'a> OrderedEditInOutPair<'a> {
             av_channels: self.in_.prev_av_channels,
             source_in: self.in_.timecode,
             source_out: self.in_.timecode,
-            record_in: self.in_.timecode,
-            record_out: self.in_.timecode,
+            record_in: self.in_.record,
+            record_out: self.in_.record,
+            speed: None,
+            handles: Handles::default(),
         }
     }
 
+    // the source the key is laid over, running for as long as the key
+    pub fn as_prev_clip_full(&self) -> Clip {
+        let tc_out = self.tc_out_with_edit_duration_if_greater();
+        let mut clip = Clip {
+            source_tape: self.in_.prev_tape.as_deref().into(),
+            edit_number: self.in_.edit_number,
+            av_channels: self.in_.prev_av_channels,
+            source_in: self.in_.timecode,
+            source_out: tc_out,
+            record_in: self.in_.record,
+            record_out: self.record_out(tc_out),
+            speed: None,
+            handles: Handles::default(),
+        };
+        clip.handles = self.handles.for_clip(&clip, self.transition_out());
+        clip
+    }
+
+    // the frames of the dissolve or wipe out of the clip, the source still plays under them
+    fn transition_out(&self) -> Option<u32> {
+        match self.out_.edit_type {
+            EditType::Dissolve | EditType::Wipe => self.out_.edit_duration_frames,
+            EditType::Cut | EditType::Key => None,
+        }
+    }
+
+    // the source out point moved onto the record timeline, which is the LTC unless record runs
+    // continuously
+    fn record_out(&self, tc_out: Timecode) -> Timecode {
+        Timecode::with_frames(
+            self.in_.record.frames() + tc_out.frames() - self.in_.timecode.frames(),
+            self.in_.record.rate(),
+        )
+        .map_or(self.out_.record, |record_out| {
+            record_out.max(self.out_.record)
+        })
+    }
+
+    // the LTC is queued counting on past midnight, so the later of the two is always the out
     fn tc_out_with_edit_duration_if_greater(&self) -> Timecode {
         self.in_
             .edit_duration_frames
@@ -276,45 +1190,19 @@ impl TryFrom<&Event> for String {
     type Error = Error;
 
     fn try_from(edit: &Event) -> Result<Self, Self::Error> {
-        let (cut_one_str, cut_two_str) = edit.get_strs()?;
-        match edit {
-            Event::Cut(clip) => {
-                let from_cmt = clip.source_tape.as_from_clip_name();
-                let from: String = EdlEditLine::from_clip(clip, cut_one_str, None)?.into();
-                Ok(format!("\n{from}{from_cmt}"))
-            }
-
-            Event::Dissolve(dissolve) => {
-                let from_cmt = dissolve.from.source_tape.as_from_clip_name();
-                let to_cmt = dissolve.to.source_tape.as_to_clip_name();
-                let from: String =
-                    EdlEditLine::from_clip(&dissolve.from, cut_one_str, None)?.into();
-                let to: String = EdlEditLine::from_clip(
-                    &dissolve.to,
-                    cut_two_str,
-                    Some(dissolve.edit_duration_frames),
-                )?
-                .into();
-                Ok(format!("\n{from}\n{to}{from_cmt}{to_cmt}"))
-            }
-
-            Event::Wipe(wipe) => {
-                let from_cmt = wipe.from.source_tape.as_from_clip_name();
-                let to_cmt = wipe.to.source_tape.as_to_clip_name();
-                let from: String = EdlEditLine::from_clip(&wipe.from, cut_one_str, None)?.into();
-                let to: String =
-                    EdlEditLine::from_clip(&wipe.to, cut_two_str, Some(wipe.edit_duration_frames))?
-                        .into();
-                Ok(format!("\n{from}\n{to}{from_cmt}{to_cmt}"))
-            }
-        }
+        let dialect = Dialect::Cmx3600;
+        dialect.format_event(edit, &mut Reels::new("", dialect.reel_width())?)
     }
 }
 
+// AX is a named source, its tape name written to the reel field as a reel name. BL, BARS and AUX
+// are written to the reel field as is, BARS as BAR where reels are three characters.
 #[derive(Debug, Clone)]
 pub enum SourceTape {
     AX(String),
     BL,
+    Bars,
+    Aux,
 }
 
 impl SourceTape {
@@ -322,20 +1210,36 @@ impl SourceTape {
         match self {
             SourceTape::AX(_) => "AX",
             SourceTape::BL => "BL",
+            SourceTape::Bars => "BARS",
+            SourceTape::Aux => "AUX",
         }
     }
 
     fn as_from_clip_name(&self) -> String {
         match self {
             SourceTape::AX(name) => format!("\n* FROM CLIP NAME: {name}"),
-            SourceTape::BL => "".into(),
+            _ => "".into(),
         }
     }
 
     fn as_to_clip_name(&self) -> String {
         match self {
             SourceTape::AX(name) => format!("\n* TO CLIP NAME: {name}"),
-            SourceTape::BL => "".into(),
+            _ => "".into(),
+        }
+    }
+
+    fn as_key_clip_name(&self) -> String {
+        match self {
+            SourceTape::AX(name) => format!("\n* KEY CLIP NAME: {name}"),
+            _ => "".into(),
+        }
+    }
+
+    fn as_source_file(&self) -> String {
+        match self {
+            SourceTape::AX(name) => format!("\n* SOURCE FILE: {name}"),
+            _ => "".into(),
         }
     }
 }
@@ -343,6 +1247,8 @@ impl SourceTape {
 impl From<Option<&str>> for SourceTape {
     fn from(opt: Option<&str>) -> Self {
         match opt {
+            Some("BARS") => SourceTape::Bars,
+            Some("AUX") => SourceTape::Aux,
             Some(name) => SourceTape::AX(name.to_string()),
             None => SourceTape::BL,
         }
@@ -353,7 +1259,7 @@ impl<'a> From<&'a SourceTape> for &'a str {
     fn from(src_tape: &'a SourceTape) -> Self {
         match src_tape {
             SourceTape::AX(name) => name.as_str(),
-            SourceTape::BL => src_tape.as_source_type(),
+            _ => src_tape.as_source_type(),
         }
     }
 }
@@ -361,14 +1267,13 @@ impl<'a> From<&'a SourceTape> for &'a str {
 impl From<&SourceTape> for Option<String> {
     fn from(src_tape: &SourceTape) -> Self {
         match src_tape {
-            SourceTape::AX(name) => Some(name.into()),
             SourceTape::BL => None,
+            _ => Some(<&str>::from(src_tape).into()),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq))]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct AVChannels {
     video: bool,
     audio: u8,
@@ -382,6 +1287,14 @@ impl AVChannels {
     pub fn video_only() -> Self {
         AVChannels::new(true, 0)
     }
+
+    pub fn video(&self) -> bool {
+        self.video
+    }
+
+    pub fn audio(&self) -> u8 {
+        self.audio
+    }
 }
 
 impl Default for AVChannels {
@@ -405,16 +1318,16 @@ impl From<AVChannels> for String {
     }
 }
 
-#[derive(Debug, Serialize)]
-#[cfg_attr(test, derive(Deserialize, Clone))]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(test, derive(Deserialize))]
 pub struct Dissolve {
     pub from: Clip,
     pub to: Clip,
     pub edit_duration_frames: u32,
 }
 
-#[derive(Debug, Serialize)]
-#[cfg_attr(test, derive(Deserialize, Clone))]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(test, derive(Deserialize))]
 pub struct Wipe {
     pub from: Clip,
     pub to: Clip,
@@ -422,6 +1335,17 @@ pub struct Wipe {
     pub edit_duration_frames: u32,
 }
 
+// a foreground source keyed over the program source, ie. a lower third over a camera
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(test, derive(Deserialize))]
+pub struct Key {
+    pub background: Clip,
+    pub foreground: Clip,
+    // the key fades up and down over these, and cuts in and out without them
+    pub key_in_frames: Option<u32>,
+    pub key_out_frames: Option<u32>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Clip {
     pub edit_number: usize,
@@ -431,6 +1355,11 @@ pub struct Clip {
     pub source_out: Timecode,
     pub record_in: Timecode,
     pub record_out: Timecode,
+    // percent of play speed for a motion effect, negative in reverse and 0 for a freeze frame
+    pub speed: Option<f32>,
+    // source frames the EDL adds around the clip. Kept apart from the source in and out points,
+    // which exports place against the record range.
+    pub handles: Handles,
 }
 
 impl Serialize for Clip {
@@ -446,6 +1375,9 @@ impl Serialize for Clip {
         state.serialize_field("source_out", &self.source_out.timecode())?;
         state.serialize_field("record_in", &self.record_in.timecode())?;
         state.serialize_field("record_out", &self.record_out.timecode())?;
+        if let Some(speed) = self.speed {
+            state.serialize_field("speed", &speed)?;
+        }
         state.end()
     }
 }
@@ -466,23 +1398,35 @@ pub struct EdlEditLine {
 impl EdlEditLine {
     fn from_clip(
         clip: &Clip,
+        reel: &str,
         edit_type: String,
         edit_duration_frames: Option<u32>,
+        dialect: Dialect,
     ) -> Result<Self, Error> {
         let edit_duration_frames = match edit_duration_frames {
             Some(n) => validate_num_size(n)?,
             None => "   ".into(),
         };
 
+        if reel.len() > dialect.reel_width() {
+            bail!(
+                "Reel '{}' is too wide for {} EDLs, which hold {} characters",
+                reel,
+                dialect,
+                dialect.reel_width()
+            );
+        }
+
+        let (source_in, source_out) = clip.handles.widen(clip);
         Ok(EdlEditLine {
-            edit_number: validate_num_size(clip.edit_number as u32)
-                .context("Cannot exceed 999 edits")?,
-            source_tape: clip.source_tape.as_source_type().into(),
-            av_channels: String::from(clip.av_channels)
+            edit_number: dialect.edit_number(clip.edit_number)?,
+            source_tape: format!("{:<width$}", reel, width = dialect.reel_width()),
+            av_channels: dialect
+                .channels(clip.av_channels)
                 .as_str()
                 .prefix_char_to_len(6, b' '),
-            source_in: clip.source_in.timecode(),
-            source_out: clip.source_out.timecode(),
+            source_in: source_in.timecode(),
+            source_out: source_out.timecode(),
             record_in: clip.record_in.timecode(),
             record_out: clip.record_out.timecode(),
             edit_duration_frames,
@@ -500,10 +1444,10 @@ impl From<EdlEditLine> for String {
             value.av_channels,
             value.edit_type,
             value.edit_duration_frames,
-            value.record_in,
-            value.record_out,
             value.source_in,
             value.source_out,
+            value.record_in,
+            value.record_out,
         )
     }
 }
@@ -521,10 +1465,7 @@ impl Prefix for &str {
 }
 
 fn validate_num_size(num: u32) -> Result<String, Error> {
-    match num.cmp(&1000) {
-        Ordering::Less => Ok(itoa::Buffer::new().format(num).prefix_char_to_len(3, b'0')),
-        _ => Err(anyhow!("Number too large {num}")),
-    }
+    dialect::pad_num(num, 3)
 }
 
 #[cfg(test)]
@@ -536,9 +1477,9 @@ mod test {
 
     use crate::utils;
 
-    impl ToString for SourceTape {
-        fn to_string(&self) -> String {
-            <&str>::from(self).to_string()
+    impl std::fmt::Display for SourceTape {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(<&str>::from(self))
         }
     }
 
@@ -552,21 +1493,21 @@ mod test {
         fn cut(&self) -> &Clip {
             match self {
                 Event::Cut(clip) => clip,
-                t @ _ => panic!("Expected Clip, got {:?}", t),
+                t => panic!("Expected Clip, got {:?}", t),
             }
         }
 
         fn dissolve(&self) -> &Dissolve {
             match self {
                 Event::Dissolve(dis) => dis,
-                t @ _ => panic!("Expected Dissolve, got {:?}", t),
+                t => panic!("Expected Dissolve, got {:?}", t),
             }
         }
 
         fn wipe(&self) -> &Wipe {
             match self {
                 Event::Wipe(wipe) => wipe,
-                t @ _ => panic!("Expected Wipe, got {:?}", t),
+                t => panic!("Expected Wipe, got {:?}", t),
             }
         }
     }
@@ -646,6 +1587,333 @@ mod test {
         assert!(files.len() == 101);
     }
 
+    #[test]
+    fn rollover_parts() {
+        let path = PathBuf::from("./test-output/rollover");
+        fs::remove_dir_all(&path).ok();
+        let dir = utils::dirs::get_or_make_dir(path).unwrap();
+        let mut edl = test_edl(&dir, "show", 2);
+        let origin = EventOrigin::new(None);
+        edl.push_edit(None, tape_edit(0)).unwrap();
+        let files: Vec<String> = (1..6)
+            .map(|i| {
+                edl.push_edit(None, tape_edit(i)).unwrap();
+                let event = edl.try_build_events(None).unwrap().remove(0);
+                let (event, file) = edl.write_event(None, event, &origin).unwrap();
+                assert_eq!(event.cut().edit_number, (i as usize - 1) % 2 + 1);
+                file
+            })
+            .collect();
+
+        assert_eq!(
+            files,
+            vec![
+                "show.edl",
+                "show.edl",
+                "show_part2.edl",
+                "show_part2.edl",
+                "show_part3.edl"
+            ]
+        );
+        let part_2 = fs::read_to_string(dir.join("show_part2.edl")).unwrap();
+        assert!(part_2.starts_with("TITLE: show PART 2\nFCM: NON-DROP FRAME\n\n001  TAPE2 "));
+        assert!(part_2.contains("* FROM CLIP NAME: tape 2"));
+        assert!(part_2.contains("\n002  TAPE3 "));
+        // the open edit is still queued for the next part
+        assert_eq!(
+            edl.tracks[0].edit_queue.front().unwrap().timecode.frames(),
+            5 * 24
+        );
+    }
+
+    #[test]
+    fn track_files() {
+        let path = PathBuf::from("./test-output/tracks");
+        fs::remove_dir_all(&path).ok();
+        let dir = utils::dirs::get_or_make_dir(path).unwrap();
+        let mut edl = test_edl(&dir, "show", 999);
+        let origin = EventOrigin::new(None);
+        let mut log = |track: Option<&str>, edit: Edit| {
+            edl.push_edit(track, edit).unwrap();
+            edl.try_build_events(track)
+                .unwrap()
+                .into_iter()
+                .map(|event| edl.write_event(track, event, &origin).unwrap().1)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(log(None, tape_edit(0)).is_empty());
+        assert!(log(Some("v2"), tape_edit(2)).is_empty());
+        assert_eq!(log(Some("V1"), tape_edit(4)), vec!["show.edl"]);
+        assert_eq!(log(Some("V2"), Edit::black(tc(6))), vec!["show_V2.edl"]);
+        assert_eq!(log(None, tape_edit(8)), vec!["show.edl"]);
+        assert!(edl.push_edit(Some("V 3"), tape_edit(8)).is_err());
+        let ended = edl.end_tracks(None, &origin).unwrap();
+        assert_eq!(ended.len(), 1);
+        assert_eq!(ended[0].1, "show_V2.edl");
+
+        let v2 = fs::read_to_string(dir.join("show_V2.edl")).unwrap();
+        assert!(v2.starts_with("TITLE: show_V2\nFCM: NON-DROP FRAME\n\n001  TAPE2 "));
+        assert!(v2.contains("\n002  BL       "));
+        assert!(v2.contains("00:00:06:00 00:00:08:00"));
+        assert!(!fs::read_to_string(dir.join("show.edl"))
+            .unwrap()
+            .contains("TAPE2"));
+    }
+
+    #[test]
+    fn correct_events() {
+        let path = PathBuf::from("./test-output/corrections");
+        fs::remove_dir_all(&path).ok();
+        let dir = utils::dirs::get_or_make_dir(path).unwrap();
+        let mut edl = test_edl(&dir, "show", 2);
+        edl.sidecar = Sidecar::new(&dir.join("show.edl")).ok();
+        let origin = EventOrigin::new(None);
+        let log = |edl: &mut Edl, i: u32| {
+            edl.push_edit(None, tape_edit(i)).unwrap();
+            for event in edl.try_build_events(None).unwrap() {
+                edl.write_event(None, event, &origin).unwrap();
+            }
+        };
+        (0..6).for_each(|i| log(&mut edl, i));
+        assert!(dir.join("show_part3.edl").is_file());
+
+        // tape 2 was a mis-fire, tape 1 plays on until tape 3
+        let (removed, rewritten) = edl.delete_event(None, Some(3), &origin).unwrap();
+        assert_eq!(<&str>::from(&removed.cut().source_tape), "tape 2");
+        assert_eq!(rewritten.len(), 3);
+        assert_eq!(rewritten[0].0.cut().record_out, tc(3));
+        assert_eq!(rewritten[1].1, "show_part2.edl");
+        assert!(!dir.join("show_part3.edl").is_file());
+
+        let patch = EditPatch {
+            source_tape: Some("tape 9".into()),
+            ..Default::default()
+        };
+        let rewritten = edl.patch_event(None, None, &patch, &origin).unwrap();
+        assert_eq!(rewritten.len(), 1);
+        let part_2 = fs::read_to_string(dir.join("show_part2.edl")).unwrap();
+        assert!(part_2.starts_with("TITLE: show PART 2\nFCM: NON-DROP FRAME\n\n001  TAPE3 "));
+        assert!(part_2.contains("\n002  TAPE9 "));
+
+        // the sidecars log each correction after the entries they correct
+        let json = fs::read_to_string(dir.join("show.json")).unwrap();
+        let corrections: Vec<(String, String, u64, String)> = json
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .skip(5)
+            .map(|entry| {
+                let clip = &entry["event"]["cut"];
+                (
+                    entry["correction"].as_str().unwrap_or_default().into(),
+                    clip["source_tape"].as_str().unwrap_or_default().into(),
+                    clip["edit_number"].as_u64().unwrap_or_default(),
+                    entry["edl_file"].as_str().unwrap_or_default().into(),
+                )
+            })
+            .collect();
+        let expected = [
+            ("deleted", "tape 2", 1, "show_part2.edl"),
+            ("rewritten", "tape 1", 2, "show.edl"),
+            ("rewritten", "tape 3", 1, "show_part2.edl"),
+            ("rewritten", "tape 4", 2, "show_part2.edl"),
+            ("patched", "tape 9", 2, "show_part2.edl"),
+        ];
+        assert_eq!(
+            corrections,
+            expected.map(|(correction, tape, n, file)| (
+                correction.into(),
+                tape.into(),
+                n,
+                file.into()
+            ))
+        );
+        let csv = fs::read_to_string(dir.join("show.csv")).unwrap();
+        assert!(csv
+            .lines()
+            .last()
+            .unwrap()
+            .ends_with(",V1,show_part2.edl,patched"));
+
+        // numbering carries on from the corrected EDL
+        log(&mut edl, 7);
+        let part_3 = fs::read_to_string(dir.join("show_part3.edl")).unwrap();
+        assert!(part_3.contains("\n001  TAPE5 "));
+        assert!(edl.delete_event(None, Some(9), &origin).is_err());
+    }
+
+    fn test_edl(dir: &Path, title: &str, rollover_edits: usize) -> Edl {
+        let (file, path) = Edl::init_file(dir, title, Ntsc::NonDropFrame).unwrap();
+        Edl {
+            title: title.into(),
+            ntsc: Ntsc::NonDropFrame,
+            dialect: Dialect::Cmx3600,
+            rollover_edits,
+            reels: Reels::new("", 8).unwrap(),
+            source_timecodes: SourceTimecodes::new("", rates::F24),
+            export_opt: ExportOpt::default(),
+            export_formats: Vec::new(),
+            pause_gap: PauseGap::LeaveOut,
+            handles: Handles::default(),
+            day_timecodes: DayTimecodes::new(Midnight::Wrap, RecordTimecode::Ltc),
+            sidecar: None,
+            tracks: vec![Track {
+                name: MAIN_TRACK.into(),
+                title: title.into(),
+                file,
+                rollover: Rollover::new(&path, rollover_edits, Dialect::Cmx3600),
+                path,
+                events: Vec::new(),
+                edit_queue: EditQueue::default(),
+                markers: Vec::new(),
+                written_markers: Vec::new(),
+            }],
+        }
+    }
+
+    fn tc(secs: u32) -> Timecode {
+        Timecode::with_frames(secs as i64 * 24, rates::F24).unwrap()
+    }
+
+    fn tape_edit(i: u32) -> Edit {
+        Edit {
+            source_tape: Some(format!("tape {i}")),
+            ..Edit::black(tc(i))
+        }
+    }
+
+    #[test]
+    fn stay_paused() {
+        let path = PathBuf::from("./test-output/pause");
+        fs::remove_dir_all(&path).ok();
+        let dir = utils::dirs::get_or_make_dir(path).unwrap();
+        let mut edl = test_edl(&dir, "show", 999);
+        edl.push_edit(None, tape_edit(1)).unwrap();
+        // paused at 5 seconds, cutting to black
+        edl.push_edit(None, Edit::black(tc(5))).unwrap();
+        assert_eq!(edl.try_build_events(None).unwrap().len(), 1);
+
+        // the edit resuming the recording couldn't be logged, so the black is still open
+        let paused = edl.resume();
+        assert!(edl.open_edits(None).unwrap().is_empty());
+        edl.stay_paused(paused);
+        let open = edl.open_edits(None).unwrap();
+        assert_eq!(open.len(), 1);
+        assert!(open[0].source_tape.is_none());
+
+        // the gap is left out once it resumes
+        edl.resume();
+        edl.push_edit(None, tape_edit(20)).unwrap();
+        assert!(edl.try_build_events(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn edits_across_midnight() {
+        let ltc = |tc: &str| Timecode::with_frames(tc, rates::F24).unwrap();
+        let mut queue = EditQueue::new();
+        let mut push = |tape: &str, edit_type: EditType, tc: &str| {
+            queue
+                .push(Edit {
+                    source_tape: Some(tape.into()),
+                    edit_type,
+                    edit_duration_frames: Some(48),
+                    ..Edit::black(ltc(tc))
+                })
+                .unwrap();
+            let out_ = queue.front().unwrap().clone();
+            queue.pop_closed().map(|in_| {
+                let event: Event = OrderedEditInOutPair::new(&in_, &out_).try_into().unwrap();
+                let event = DayTimecodes::new(Midnight::Wrap, RecordTimecode::Ltc).apply(&event);
+                let clip: &Clip = (&event).into();
+                format!(
+                    "{} {} {}",
+                    clip.source_in.timecode(),
+                    clip.source_out.timecode(),
+                    clip.record_out.timecode()
+                )
+            })
+        };
+        push("a", EditType::Cut, "23:59:50:00");
+        assert_eq!(
+            push("b", EditType::Dissolve, "23:59:59:00").unwrap(),
+            "23:59:50:00 23:59:59:00 23:59:59:00"
+        );
+        // the dissolve runs on past the next edit, through midnight
+        assert_eq!(
+            push("c", EditType::Cut, "00:00:00:12").unwrap(),
+            "23:59:59:00 00:00:01:00 00:00:01:00"
+        );
+        assert_eq!(
+            push("d", EditType::Cut, "00:00:10:00").unwrap(),
+            "00:00:00:12 00:00:10:00 00:00:10:00"
+        );
+    }
+
+    #[test]
+    fn source_handles() {
+        let frame_in = OrderedEdit {
+            edit_number: 1,
+            edit_type: EditType::Cut,
+            source_tape: Some("tape1".into()),
+            prev_tape: None,
+            av_channels: AVChannels::default(),
+            prev_av_channels: AVChannels::default(),
+            timecode: tc(1),
+            record: tc(1),
+            edit_duration_frames: None,
+            wipe_num: None,
+            key_out_frames: None,
+            motion: None,
+        };
+        let frame_out = OrderedEdit {
+            edit_number: 2,
+            edit_type: EditType::Dissolve,
+            source_tape: Some("tape2".into()),
+            prev_tape: Some("tape1".into()),
+            timecode: tc(10),
+            record: tc(10),
+            edit_duration_frames: Some(12),
+            ..frame_in.clone()
+        };
+        let handles = Handles { head: 48, tail: 24 };
+        let event: Event = OrderedEditInOutPair::new(&frame_in, &frame_out)
+            .with_handles(handles)
+            .try_into()
+            .unwrap();
+        // the clip keeps its logged source, as exports place it against the record range
+        assert_eq!(event.cut().source_in, tc(1));
+        assert_eq!(event.cut().source_out, tc(10));
+        assert_eq!(event.cut().handles, Handles { head: 48, tail: 36 });
+        // in the EDL the head stops at the start of the timecode, the tail runs on past the
+        // dissolve out
+        let line = String::try_from(&event).unwrap();
+        assert!(line.contains(" C        00:00:00:00 00:00:11:12 00:00:01:00 00:00:10:00\n"));
+
+        let frame_in = OrderedEdit {
+            edit_number: 2,
+            ..frame_out.clone()
+        };
+        let frame_out = OrderedEdit {
+            edit_type: EditType::Cut,
+            timecode: tc(20),
+            record: tc(20),
+            ..frame_out
+        };
+        let event: Event = OrderedEditInOutPair::new(&frame_in, &frame_out)
+            .with_handles(handles)
+            .try_into()
+            .unwrap();
+        let dissolve = event.dissolve();
+        assert_eq!(dissolve.to.source_in, tc(10));
+        assert_eq!(dissolve.to.source_out, tc(20));
+        // the outgoing source only marks where the dissolve starts
+        assert_eq!(dissolve.from.source_in, tc(10));
+        assert_eq!(dissolve.from.source_out, tc(10));
+        let lines = String::try_from(&event).unwrap();
+        assert!(lines.contains(" C        00:00:10:00 00:00:10:00 00:00:10:00 00:00:10:00\n"));
+        assert!(lines.contains(" D    012 00:00:08:00 00:00:21:00 00:00:10:00 00:00:20:00\n"));
+    }
+
     #[test]
     fn edit_req_into() {
         let tc_1 = Timecode::with_frames("01:00:00:00", rates::F24).unwrap();
@@ -659,8 +1927,11 @@ mod test {
             av_channels: AVChannels::default(),
             prev_av_channels: AVChannels::default(),
             timecode: tc_1,
+            record: tc_1,
             edit_duration_frames: Some(10),
             wipe_num: Some(1),
+            key_out_frames: None,
+            motion: None,
         };
         let frame_out = OrderedEdit {
             edit_number: 2,
@@ -670,8 +1941,11 @@ mod test {
             av_channels: AVChannels::default(),
             prev_av_channels: AVChannels::default(),
             timecode: tc_2,
+            record: tc_2,
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
+            motion: None,
         };
         let edit: Event = OrderedEditInOutPair::new(&frame_in, &frame_out)
             .try_into()
@@ -702,8 +1976,11 @@ mod test {
             av_channels: AVChannels::default(),
             prev_av_channels: AVChannels::default(),
             timecode: tc_1,
+            record: tc_1,
             edit_duration_frames: Some(10),
             wipe_num: Some(1),
+            key_out_frames: None,
+            motion: None,
         };
         let frame_out = OrderedEdit {
             edit_number: 2,
@@ -713,8 +1990,11 @@ mod test {
             av_channels: AVChannels::default(),
             prev_av_channels: AVChannels::default(),
             timecode: tc_2,
+            record: tc_2,
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
+            motion: None,
         };
         let edit: Event = OrderedEditInOutPair::new(&frame_in, &frame_out)
             .try_into()
@@ -739,8 +2019,11 @@ mod test {
             av_channels: AVChannels::default(),
             prev_av_channels: AVChannels::default(),
             timecode: tc_2,
+            record: tc_2,
             edit_duration_frames: None,
             wipe_num: Some(1),
+            key_out_frames: None,
+            motion: None,
         };
         let frame_out = OrderedEdit {
             edit_number: 2,
@@ -750,8 +2033,11 @@ mod test {
             av_channels: AVChannels::default(),
             prev_av_channels: AVChannels::default(),
             timecode: tc_3,
+            record: tc_3,
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
+            motion: None,
         };
         let edit: Event = OrderedEditInOutPair::new(&frame_in, &frame_out)
             .try_into()
@@ -770,8 +2056,11 @@ mod test {
             av_channels: AVChannels::default(),
             prev_av_channels: AVChannels::default(),
             timecode: tc_2,
+            record: tc_2,
             edit_duration_frames: Some(10),
             wipe_num: Some(1),
+            key_out_frames: None,
+            motion: None,
         };
         let frame_out = OrderedEdit {
             edit_number: 2,
@@ -781,8 +2070,11 @@ mod test {
             av_channels: AVChannels::default(),
             prev_av_channels: AVChannels::default(),
             timecode: tc_3,
+            record: tc_3,
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
+            motion: None,
         };
         let edit: Event = OrderedEditInOutPair::new(&frame_in, &frame_out)
             .try_into()
@@ -799,6 +2091,63 @@ mod test {
             tc_2 + Timecode::with_frames(frame_in.edit_duration_frames.unwrap(), rates::F24)
                 .unwrap()
         );
+
+        // a source played at half speed, then in double speed reverse back from its source in
+        let tc = |tc: &str| Timecode::with_frames(tc, rates::F24).unwrap();
+        let motion = |speed| {
+            Some(Motion {
+                speed,
+                source_in: tc("10:00:00:00"),
+            })
+        };
+        let frame_out = OrderedEdit {
+            timecode: tc("01:05:20:00"),
+            record: tc("01:05:20:00"),
+            ..frame_out
+        };
+        let frame_in = OrderedEdit {
+            edit_type: EditType::Cut,
+            timecode: tc_2,
+            record: tc_2,
+            motion: motion(50.0),
+            ..frame_out.clone()
+        };
+        let edit: Event = OrderedEditInOutPair::new(&frame_in, &frame_out)
+            .try_into()
+            .unwrap();
+        assert_eq!(edit.cut().speed, Some(50.0));
+        assert_eq!(edit.cut().source_in, tc("10:00:00:00"));
+        assert_eq!(edit.cut().source_out, tc("10:00:05:00"));
+        assert_eq!(edit.cut().record_in, tc_2);
+        assert_eq!(edit.cut().record_out, tc("01:05:20:00"));
+
+        let frame_in = OrderedEdit {
+            motion: motion(-200.0),
+            ..frame_in
+        };
+        let edit: Event = OrderedEditInOutPair::new(&frame_in, &frame_out)
+            .try_into()
+            .unwrap();
+        assert_eq!(edit.cut().source_in, tc("09:59:40:00"));
+        assert_eq!(edit.cut().source_out, tc("10:00:00:00"));
+
+        // on a continuous record timeline the source stays on the LTC
+        let frame_in = OrderedEdit {
+            record: tc("00:59:50:00"),
+            motion: None,
+            ..frame_in
+        };
+        let frame_out = OrderedEdit {
+            record: tc("01:00:00:00"),
+            ..frame_out
+        };
+        let edit: Event = OrderedEditInOutPair::new(&frame_in, &frame_out)
+            .try_into()
+            .unwrap();
+        assert_eq!(edit.cut().source_in, tc_2);
+        assert_eq!(edit.cut().source_out, tc("01:05:20:00"));
+        assert_eq!(edit.cut().record_in, tc("00:59:50:00"));
+        assert_eq!(edit.cut().record_out, tc("01:00:00:00"));
     }
 
     #[test]
@@ -815,6 +2164,8 @@ mod test {
             source_out: tc_2,
             record_in: tc_1,
             record_out: tc_2,
+            speed: None,
+            handles: Handles::default(),
         };
         let clip_2 = Clip {
             edit_number: 2,
@@ -824,13 +2175,16 @@ mod test {
             source_out: tc_4,
             record_in: tc_3,
             record_out: tc_4,
+            speed: None,
+            handles: Handles::default(),
         };
 
         let cut = &Event::Cut(clip_1.clone());
         let cut_string: String = cut.try_into().unwrap();
         let cut_cmp: String = "
-001  AX    AA/V  C        01:00:00:00 01:05:10:00 01:00:00:00 01:05:10:00
-* FROM CLIP NAME: test_clip.mov"
+001  TESTCLIP    AA/V  C        01:00:00:00 01:05:10:00 01:00:00:00 01:05:10:00
+* FROM CLIP NAME: test_clip.mov
+* SOURCE FILE: test_clip.mov"
             .into();
         assert_eq!(cut_string, cut_cmp);
 
@@ -842,10 +2196,12 @@ mod test {
         });
         let wipe_string: String = wipe.try_into().unwrap();
         let wipe_cmp: String = "
-001  AX    AA/V  C        01:00:00:00 01:05:10:00 01:00:00:00 01:05:10:00
-002  AX   AAA/V  W001 015 01:10:00:00 01:15:00:00 01:10:00:00 01:15:00:00
+001  TESTCLIP    AA/V  C        01:00:00:00 01:05:10:00 01:00:00:00 01:05:10:00
+002  TESTC37Y   AAA/V  W001 015 01:10:00:00 01:15:00:00 01:10:00:00 01:15:00:00
 * FROM CLIP NAME: test_clip.mov
-* TO CLIP NAME: test_clip_2.mov"
+* TO CLIP NAME: test_clip_2.mov
+* SOURCE FILE: test_clip.mov
+* SOURCE FILE: test_clip_2.mov"
             .into();
         assert_eq!(wipe_string, wipe_cmp);
 
@@ -856,10 +2212,12 @@ mod test {
         });
         let dissolve_string: String = dissove.try_into().unwrap();
         let dissove_cmp: String = "
-001  AX    AA/V  C        01:00:00:00 01:05:10:00 01:00:00:00 01:05:10:00
-002  AX   AAA/V  D    000 01:10:00:00 01:15:00:00 01:10:00:00 01:15:00:00
+001  TESTCLIP    AA/V  C        01:00:00:00 01:05:10:00 01:00:00:00 01:05:10:00
+002  TESTC37Y   AAA/V  D    000 01:10:00:00 01:15:00:00 01:10:00:00 01:15:00:00
 * FROM CLIP NAME: test_clip.mov
-* TO CLIP NAME: test_clip_2.mov"
+* TO CLIP NAME: test_clip_2.mov
+* SOURCE FILE: test_clip.mov
+* SOURCE FILE: test_clip_2.mov"
             .into();
         assert_eq!(dissolve_string, dissove_cmp);
     }
@@ -0,0 +1,531 @@
+// reads CMX3600 text back into `Event`s, for reloading, validating and converting EDLs written
+// by edl-gen or other tools. Tape names are taken from `* SOURCE FILE` comments, as edl-gen
+// writes them, then from `* FROM/TO CLIP NAME` comments when the reel is written as AX, and from
+// the reel column otherwise.
+
+use vtc::{Framerate, Timecode};
+
+use std::fmt;
+
+use crate::{
+    edl_writer::{AVChannels, Clip, Dissolve, Event, Handles, Key, Ntsc, SourceTape, Wipe},
+    ltc_decoder::TCError,
+};
+
+#[derive(Debug)]
+pub struct ParsedEdl {
+    pub title: Option<String>,
+    pub ntsc: Option<Ntsc>,
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// `fps` is the frame rate the EDL was logged at, as CMX3600 only records whether it's drop frame.
+pub fn parse(edl: &str, fps: f32) -> Result<ParsedEdl, ParseError> {
+    let mut parser = Parser {
+        fps,
+        title: None,
+        ntsc: None,
+        rate: None,
+        events: Vec::new(),
+        pending: None,
+        source_files: 0,
+    };
+    for (i, line) in edl.lines().enumerate() {
+        parser.line(line.trim()).map_err(|message| ParseError {
+            line: i + 1,
+            message,
+        })?;
+    }
+    parser.finish_pending();
+    Ok(ParsedEdl {
+        title: parser.title,
+        ntsc: parser.ntsc,
+        events: parser.events,
+    })
+}
+
+struct Parser {
+    fps: f32,
+    title: Option<String>,
+    ntsc: Option<Ntsc>,
+    rate: Option<Framerate>,
+    events: Vec<Event>,
+    // the last event read, held until any clip name comments following it are applied
+    pending: Option<Event>,
+    // source file comments applied to the pending event so far
+    source_files: usize,
+}
+
+impl Parser {
+    fn line(&mut self, line: &str) -> Result<(), String> {
+        if line.is_empty() {
+            return Ok(());
+        }
+        if let Some(title) = line.strip_prefix("TITLE:") {
+            self.title = Some(title.trim().into());
+            return Ok(());
+        }
+        if let Some(fcm) = line.strip_prefix("FCM:") {
+            let ntsc =
+                Ntsc::try_from(fcm.trim()).map_err(|_| format!("Invalid FCM '{}'", fcm.trim()))?;
+            self.ntsc = Some(ntsc);
+            self.rate = None;
+            return Ok(());
+        }
+        if let Some(name) = line.strip_prefix("* FROM CLIP NAME:") {
+            return self.clip_name(name.trim(), ClipSide::From);
+        }
+        if let Some(name) = line.strip_prefix("* TO CLIP NAME:") {
+            return self.clip_name(name.trim(), ClipSide::To);
+        }
+        if let Some(name) = line.strip_prefix("* KEY CLIP NAME:") {
+            return self.clip_name(name.trim(), ClipSide::Key);
+        }
+        if let Some(name) = line.strip_prefix("* SOURCE FILE:") {
+            return self.source_file(name.trim());
+        }
+        if let Some(motion) = line.strip_prefix("M2") {
+            return self.motion(motion);
+        }
+        // other comments and lines edl-gen has no use for, ie. audio routing
+        if ["*", "SPLIT", "AUD", ">>>"]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            return Ok(());
+        }
+        self.event_line(line)
+    }
+
+    fn event_line(&mut self, line: &str) -> Result<(), String> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (edit_number, reel, channels, edit_type, duration, timecodes) = match fields[..] {
+            [num, reel, channels, edit_type, a, b, c, d] => {
+                (num, reel, channels, edit_type, None, [a, b, c, d])
+            }
+            [num, reel, channels, edit_type, duration, a, b, c, d] => {
+                (num, reel, channels, edit_type, Some(duration), [a, b, c, d])
+            }
+            _ => return Err(format!("Unrecognized line '{}'", line)),
+        };
+        self.source_files = 0;
+
+        let edit_number = edit_number
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid edit number '{}'", edit_number))?;
+        let rate = self.rate()?;
+        let [source_in, source_out, record_in, record_out] = timecodes.map(|tc| {
+            Timecode::with_frames(tc, rate)
+                .map_err(|e| format!("Invalid timecode '{}': {}", tc, e.into_msg()))
+        });
+        let clip = Clip {
+            edit_number,
+            source_tape: match reel {
+                "BL" => SourceTape::BL,
+                reel => Some(reel).into(),
+            },
+            av_channels: parse_channels(channels)?,
+            source_in: source_in?,
+            source_out: source_out?,
+            record_in: record_in?,
+            record_out: record_out?,
+            speed: None,
+            handles: Handles::default(),
+        };
+        // the background of a key is written `K B`, across the edit type and duration columns
+        let (edit_type, duration) = match (edit_type, duration) {
+            ("K", Some("B")) => ("KB", None),
+            fields => fields,
+        };
+        let duration = duration
+            .map(|duration| {
+                duration
+                    .parse::<u32>()
+                    .map_err(|_| format!("Invalid transition duration '{}'", duration))
+            })
+            .transpose()?;
+
+        match (edit_type, duration) {
+            ("C", None) => {
+                self.finish_pending();
+                self.pending = Some(Event::Cut(clip));
+                Ok(())
+            }
+            ("C", Some(_)) => Err("Cut can not have a transition duration".into()),
+            ("KB", None) => {
+                self.finish_pending();
+                self.pending = Some(Event::Cut(clip));
+                Ok(())
+            }
+            ("K", key_in_frames) => {
+                let background = self.take_from_clip(edit_number)?;
+                self.pending = Some(Event::Key(Key {
+                    background,
+                    foreground: clip,
+                    key_in_frames,
+                    key_out_frames: None,
+                }));
+                Ok(())
+            }
+            ("KO", key_out_frames) => match self.pending.as_mut() {
+                Some(Event::Key(key)) if key.foreground.edit_number == edit_number => {
+                    key.key_out_frames = key_out_frames;
+                    Ok(())
+                }
+                _ => {
+                    let background = self.take_from_clip(edit_number)?;
+                    self.pending = Some(Event::Key(Key {
+                        background,
+                        foreground: clip,
+                        key_in_frames: None,
+                        key_out_frames,
+                    }));
+                    Ok(())
+                }
+            },
+            (_, None) if edit_type == "D" || edit_type.starts_with('W') => {
+                Err(format!("Edit type '{}' requires a duration", edit_type))
+            }
+            ("D", Some(edit_duration_frames)) => {
+                let from = self.take_from_clip(edit_number)?;
+                self.pending = Some(Event::Dissolve(Dissolve {
+                    from,
+                    to: clip,
+                    edit_duration_frames,
+                }));
+                Ok(())
+            }
+            (wipe, Some(edit_duration_frames)) if wipe.starts_with('W') => {
+                let wipe_number = wipe[1..]
+                    .parse::<u32>()
+                    .map_err(|_| format!("Invalid wipe number '{}'", wipe))?;
+                let from = self.take_from_clip(edit_number)?;
+                self.pending = Some(Event::Wipe(Wipe {
+                    from,
+                    to: clip,
+                    wipe_number,
+                    edit_duration_frames,
+                }));
+                Ok(())
+            }
+            (edit_type, _) => Err(format!("Unsupported edit type '{}'", edit_type)),
+        }
+    }
+
+    // a transition is written as a cut to the outgoing source followed by the transition line,
+    // both under the same edit number
+    fn take_from_clip(&mut self, edit_number: usize) -> Result<Clip, String> {
+        match self.pending.take() {
+            Some(Event::Cut(clip)) if clip.edit_number == edit_number => Ok(clip),
+            pending => {
+                self.pending = pending;
+                Err(format!(
+                    "Transition for edit {:03} has no outgoing source line",
+                    edit_number
+                ))
+            }
+        }
+    }
+
+    fn clip_name(&mut self, name: &str, side: ClipSide) -> Result<(), String> {
+        let clip = match (self.pending.as_mut(), side) {
+            (Some(Event::Cut(clip)), ClipSide::From) => clip,
+            (Some(Event::Dissolve(dissolve)), ClipSide::From) => &mut dissolve.from,
+            (Some(Event::Dissolve(dissolve)), ClipSide::To) => &mut dissolve.to,
+            (Some(Event::Wipe(wipe)), ClipSide::From) => &mut wipe.from,
+            (Some(Event::Wipe(wipe)), ClipSide::To) => &mut wipe.to,
+            (Some(Event::Key(key)), ClipSide::From) => &mut key.background,
+            (Some(Event::Key(key)), ClipSide::To | ClipSide::Key) => &mut key.foreground,
+            (Some(_), ClipSide::Key) => return Err("KEY CLIP NAME comment without a key".into()),
+            (Some(Event::Cut(_)), ClipSide::To) => {
+                return Err("TO CLIP NAME comment on a cut".into())
+            }
+            (None, _) => return Err("Clip name comment before any edit".into()),
+        };
+        if let SourceTape::AX(reel) = &mut clip.source_tape {
+            if reel == "AX" {
+                *reel = name.into();
+            }
+        }
+        Ok(())
+    }
+
+    // written once per named source of an event, in the order of its lines
+    fn source_file(&mut self, name: &str) -> Result<(), String> {
+        let clips = match self.pending.as_mut() {
+            Some(Event::Cut(clip)) => vec![clip],
+            Some(Event::Dissolve(Dissolve { from, to, .. }))
+            | Some(Event::Wipe(Wipe { from, to, .. })) => vec![from, to],
+            Some(Event::Key(Key {
+                background,
+                foreground,
+                ..
+            })) => vec![background, foreground],
+            None => return Err("Source file comment before any edit".into()),
+        };
+        if let Some(clip) = clips
+            .into_iter()
+            .filter(|clip| matches!(clip.source_tape, SourceTape::AX(_)))
+            .nth(self.source_files)
+        {
+            clip.source_tape = SourceTape::AX(name.into());
+        }
+        self.source_files += 1;
+        Ok(())
+    }
+
+    // `M2 reel fps timecode`, the speed of the incoming source of the event it follows
+    fn motion(&mut self, fields: &str) -> Result<(), String> {
+        let fps = match fields.split_whitespace().collect::<Vec<_>>()[..] {
+            [_, fps, _] => fps,
+            _ => return Err(format!("Unrecognized motion effect 'M2{}'", fields)),
+        };
+        let fps = fps
+            .parse::<f32>()
+            .map_err(|_| format!("Invalid motion effect speed '{}'", fps))?;
+        let timebase = self.rate()?.timebase().to_integer() as f32;
+        let clip = match self.pending.as_mut() {
+            Some(Event::Cut(clip)) => clip,
+            Some(Event::Dissolve(Dissolve { to, .. })) | Some(Event::Wipe(Wipe { to, .. })) => to,
+            Some(Event::Key(key)) => &mut key.foreground,
+            None => return Err("Motion effect before any edit".into()),
+        };
+        clip.speed = Some((fps / timebase * 1000.0).round() / 10.0);
+        Ok(())
+    }
+
+    fn finish_pending(&mut self) {
+        if let Some(event) = self.pending.take() {
+            self.events.push(event);
+        }
+    }
+
+    fn rate(&mut self) -> Result<Framerate, String> {
+        if let Some(rate) = self.rate {
+            return Ok(rate);
+        }
+        let ntsc = self.ntsc.unwrap_or(Ntsc::NonDropFrame);
+        let rate = Framerate::with_playback(self.fps, ntsc.as_vtc())
+            .map_err(|e| format!("Invalid frame rate: {}", e.into_msg()))?;
+        self.rate = Some(rate);
+        Ok(rate)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ClipSide {
+    From,
+    To,
+    Key,
+}
+
+// channel columns as edl-gen writes them (ex. `V`, `AA`, `AA/V`, `A12/V`), along with the
+// `A2`, `B` and `NONE` forms other tools use
+fn parse_channels(channels: &str) -> Result<AVChannels, String> {
+    let invalid = || format!("Invalid AV channels '{}'", channels);
+    let (audio, video) = match channels {
+        "NONE" => return Ok(AVChannels::new(false, 0)),
+        "B" => return Ok(AVChannels::new(true, 1)),
+        "V" => return Ok(AVChannels::video_only()),
+        channels => match channels.strip_suffix("/V") {
+            Some(audio) => (audio, true),
+            None => (channels, false),
+        },
+    };
+    let audio = match audio.strip_prefix('A') {
+        Some("") => 1,
+        Some(rest) if rest.chars().all(|c| c == 'A') => rest.len() as u8 + 1,
+        // numbered channels, ie. A2 or A12, count up to the highest channel
+        Some(tracks) if tracks.chars().all(|c| c.is_ascii_digit()) => tracks
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .max()
+            .unwrap_or(0)
+            as u8,
+        Some(_) => return Err(invalid()),
+        None => return Err(invalid()),
+    };
+    Ok(AVChannels::new(video, audio))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::test::{clip, session_events};
+    use vtc::rates;
+
+    fn write(events: &[Event]) -> String {
+        events.iter().fold(
+            "TITLE: my show\nFCM: NON-DROP FRAME".to_string(),
+            |acc, event| format!("{acc}\n{}", String::try_from(event).unwrap()),
+        )
+    }
+
+    #[test]
+    fn round_trip() {
+        let events = session_events(rates::F24);
+        let parsed = parse(&write(&events), 24.0).unwrap();
+
+        assert_eq!(parsed.title.as_deref(), Some("my show"));
+        assert_eq!(parsed.ntsc, Some(Ntsc::NonDropFrame));
+        assert_eq!(
+            serde_json::to_value(&parsed.events).unwrap(),
+            serde_json::to_value(&events).unwrap()
+        );
+    }
+
+    #[test]
+    fn other_tools() {
+        let edl = "TITLE: other\n\
+                   FCM: DROP FRAME\n\
+                   \n\
+                   001  TAPE01   B     C        00:59:58;00 01:00:00;00 01:00:00;00 01:00:02;00\n\
+                   M2   TAPE01       030.0                00:59:58;00\n\
+                   002  TAPE01   A2    C        01:00:00;00 01:00:00;00 01:00:02;00 01:00:02;00\n\
+                   002  BL       A2    D    015 00:00:00;00 00:00:01;00 01:00:02;00 01:00:03;00\n\
+                   * TO CLIP NAME: ignored.mov\n\
+                   003  BARS     V     C        00:00:00;00 00:00:05;00 01:00:03;00 01:00:08;00\n\
+                   004  A001C003 V     C        12:00:00;00 12:00:02;00 01:00:08;00 01:00:10;00\n\
+                   * FROM CLIP NAME: interview\n\
+                   * SOURCE FILE: A001C003_220101.mov\n";
+        let parsed = parse(edl, 29.97).unwrap();
+
+        assert_eq!(parsed.events.len(), 4);
+        let Event::Cut(cut) = &parsed.events[0] else {
+            panic!("Expected cut");
+        };
+        assert_eq!(<&str>::from(&cut.source_tape), "TAPE01");
+        assert_eq!(cut.av_channels, AVChannels::new(true, 1));
+        // vtc renders this drop frame count as 00:59:58;01, so check the frame itself
+        assert_eq!(cut.source_in.frames(), 107_832);
+        assert_eq!(cut.record_in.timecode(), "01:00:00;00");
+        assert_eq!(cut.speed, Some(100.0));
+        let Event::Dissolve(dissolve) = &parsed.events[1] else {
+            panic!("Expected dissolve");
+        };
+        assert_eq!(<&str>::from(&dissolve.from.source_tape), "TAPE01");
+        assert!(matches!(dissolve.to.source_tape, SourceTape::BL));
+        assert_eq!(dissolve.to.av_channels, AVChannels::new(false, 2));
+        assert_eq!(dissolve.edit_duration_frames, 15);
+        let bars: &Clip = (&parsed.events[2]).into();
+        assert!(matches!(bars.source_tape, SourceTape::Bars));
+        let file: &Clip = (&parsed.events[3]).into();
+        assert_eq!(<&str>::from(&file.source_tape), "A001C003_220101.mov");
+    }
+
+    #[test]
+    fn key_events() {
+        let rate = rates::F24;
+        let key = |edit_number, tc: (&str, &str), key_in_frames, key_out_frames| {
+            Event::Key(Key {
+                background: clip(
+                    edit_number,
+                    Some("cam1"),
+                    AVChannels::video_only(),
+                    tc,
+                    rate,
+                ),
+                foreground: clip(
+                    edit_number,
+                    Some("title.png"),
+                    AVChannels::video_only(),
+                    tc,
+                    rate,
+                ),
+                key_in_frames,
+                key_out_frames,
+            })
+        };
+        let events = vec![
+            key(1, ("01:00:00:00", "01:00:05:00"), Some(10), None),
+            key(2, ("01:00:05:00", "01:00:10:00"), None, Some(12)),
+            key(3, ("01:00:10:00", "01:00:15:00"), Some(8), Some(6)),
+        ];
+        let edl = write(&events);
+
+        assert!(edl.contains("003  CAM1           V  K B      01:00:10:00"));
+        assert!(edl.contains("003  TITLE          V  K    008 01:00:10:00"));
+        assert!(edl.contains("003  TITLE          V  KO   006 01:00:10:00"));
+        assert!(edl.contains("002  TITLE          V  KO   012 01:00:05:00"));
+        assert!(edl.contains("* KEY CLIP NAME: title.png"));
+        let parsed = parse(&edl, 24.0).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed.events).unwrap(),
+            serde_json::to_value(&events).unwrap()
+        );
+    }
+
+    #[test]
+    fn motion_effects() {
+        let rate = rates::F24;
+        let mut events = session_events(rate);
+        for (event, speed) in events.iter_mut().zip([50.0, 0.0, -200.0]) {
+            match event {
+                Event::Cut(clip) => clip.speed = Some(speed),
+                Event::Dissolve(Dissolve { to, .. }) | Event::Wipe(Wipe { to, .. }) => {
+                    to.speed = Some(speed)
+                }
+                Event::Key(key) => key.foreground.speed = Some(speed),
+            }
+        }
+        // half speed off a replay deck, its source running apart from the record timeline
+        if let Event::Cut(clip) = &mut events[0] {
+            clip.source_in = Timecode::with_frames("10:00:00:00", rate).unwrap();
+            clip.source_out = Timecode::with_frames("10:00:05:00", rate).unwrap();
+        }
+        let edl = write(&events);
+
+        assert!(edl.contains(
+            "C        10:00:00:00 10:00:05:00 01:00:00:00 01:00:10:00\n\
+             M2   CAMERTG9       012.0                10:00:00:00\n"
+        ));
+        assert!(edl.contains("\nM2   CAM2           000.0                01:00:10:00\n"));
+        assert!(edl.contains("\nM2   CAMERTG9      -048.0                01:00:20:00\n"));
+        let parsed = parse(&edl, 24.0).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed.events).unwrap(),
+            serde_json::to_value(&events).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = |edl: &str| parse(edl, 24.0).unwrap_err().to_string();
+
+        assert_eq!(
+            err("TITLE: x\n\n001  AX  V  C  01:00:00:00 01:00:01:00 01:00:00:00"),
+            "Line 3: Unrecognized line '001  AX  V  C  01:00:00:00 01:00:01:00 01:00:00:00'"
+        );
+        assert_eq!(
+            err("FCM: SOMETIMES DROP FRAME"),
+            "Line 1: Invalid FCM 'SOMETIMES DROP FRAME'"
+        );
+        assert_eq!(
+            err("001  AX  V  D  010 01:00:00:00 01:00:01:00 01:00:00:00 01:00:01:00"),
+            "Line 1: Transition for edit 001 has no outgoing source line"
+        );
+        assert_eq!(
+            err("001  AX  Q  C  01:00:00:00 01:00:01:00 01:00:00:00 01:00:01:00"),
+            "Line 1: Invalid AV channels 'Q'"
+        );
+        assert_eq!(
+            err("001  AX  V  W1X 010 01:00:00:00 01:00:01:00 01:00:00:00 01:00:01:00"),
+            "Line 1: Invalid wipe number 'W1X'"
+        );
+        assert!(err("\n* FROM CLIP NAME: cam1").starts_with("Line 2: "));
+    }
+}
@@ -0,0 +1,182 @@
+// Reel names for the EDL reel column. Avid and linear suites conform on the reel rather than the
+// clip name comment, so each source tape is given a reel that fits the dialect's reel width.
+// Tapes can be given an explicit reel ID, any other tape name is shortened to an upper case
+// alphanumeric reel. A name that doesn't fit is cut short and ends in a hash of the full tape
+// name. Reels are kept for the session, so a tape gets the same reel on every track, and a tape
+// whose reel is already taken, in any case, moves on to the next hash until one is free.
+
+use anyhow::{anyhow, Error};
+
+use std::path::Path;
+
+use crate::edl_writer::SourceTape;
+
+// reels with a meaning of their own, never handed out to a tape
+const RESERVED: [&str; 5] = ["AX", "BL", "BARS", "BAR", "AUX"];
+
+#[derive(Debug)]
+pub struct Reels {
+    width: usize,
+    // tape name and reel, explicit IDs first then each shortened name as it's first used
+    assigned: Vec<(String, String)>,
+}
+
+impl Reels {
+    // `reel_ids` holds one `tape=REEL` per line
+    pub fn new(reel_ids: &str, width: usize) -> Result<Self, Error> {
+        let mut assigned: Vec<(String, String)> = Vec::new();
+        for (tape, reel) in parse_tape_pairs(reel_ids) {
+            let reel: String = reel.split_whitespace().collect();
+            if reel.len() > width {
+                log::warn!("Reel ID '{reel}' for '{tape}' is longer than {width} characters");
+            }
+            let reel: String = reel.chars().take(width).collect();
+            if RESERVED.contains(&reel.as_str()) {
+                return Err(anyhow!("Reel ID '{}' for '{}' is reserved", reel, tape));
+            }
+            if let Some((other, _)) = assigned
+                .iter()
+                .find(|(other, r)| r.eq_ignore_ascii_case(&reel) && *other != tape)
+            {
+                return Err(anyhow!(
+                    "Reel ID '{}' for '{}' is already used by '{}'",
+                    reel,
+                    tape,
+                    other
+                ));
+            }
+            assigned.push((tape, reel));
+        }
+        Ok(Reels { width, assigned })
+    }
+
+    pub fn reel(&mut self, source_tape: &SourceTape) -> Result<String, Error> {
+        let SourceTape::AX(tape) = source_tape else {
+            // BARS is written as BAR on three character reels
+            return Ok(source_tape
+                .as_source_type()
+                .chars()
+                .take(self.width)
+                .collect());
+        };
+        if let Some((_, reel)) = self.assigned.iter().find(|(name, _)| name == tape) {
+            return Ok(reel.clone());
+        }
+        let reel = self
+            .candidates(tape)
+            .find(|reel| {
+                !RESERVED.contains(&reel.as_str())
+                    && !self
+                        .assigned
+                        .iter()
+                        .any(|(_, r)| r.eq_ignore_ascii_case(reel))
+            })
+            .ok_or_else(|| anyhow!("No reel left for '{}'", tape))?;
+        self.assigned.push((tape.clone(), reel.clone()));
+        Ok(reel)
+    }
+
+    // the tape name as a reel when it fits, then the name cut short with each hash in turn
+    fn candidates<'a>(&self, tape: &'a str) -> impl Iterator<Item = String> + 'a {
+        let stem = Path::new(tape)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(tape);
+        let base: String = stem
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        let whole = Some(base.clone()).filter(|base| !base.is_empty() && base.len() <= self.width);
+        let base = if base.is_empty() { "REEL".into() } else { base };
+        // up to three hash characters, with the rest of the width kept for the name
+        let digits = self.width.div_ceil(2).min(3);
+        let keep = (self.width - digits).min(base.len());
+        whole.into_iter().chain(
+            (0..=u8::MAX).map(move |salt| format!("{}{}", &base[..keep], hash(tape, salt, digits))),
+        )
+    }
+}
+
+// FNV-1a of the tape name, as `digits` upper case base 36 characters. Stable across runs and
+// platforms, unlike the std hasher.
+fn hash(tape: &str, salt: u8, digits: usize) -> String {
+    let mut hash = 0xcbf29ce484222325_u64;
+    for byte in tape.bytes().chain([salt]) {
+        hash = (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3);
+    }
+    (0..digits)
+        .map(|_| {
+            let digit = (hash % 36) as u32;
+            hash /= 36;
+            char::from_digit(digit, 36)
+                .unwrap_or('0')
+                .to_ascii_uppercase()
+        })
+        .collect()
+}
+
+// `tape=value` lines, as used for reel IDs and source labels
+pub(crate) fn parse_tape_pairs(lines: &str) -> Vec<(String, String)> {
+    lines
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(tape, value)| (tape.trim().to_string(), value.trim().to_string()))
+        .filter(|(tape, value)| !tape.is_empty() && !value.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shorten_reels() {
+        let mut reels = Reels::new("cam2=CAM_B\ncam3 = LONGREELNAME", 8).unwrap();
+        let mut reel = |tape: &str| reels.reel(&Some(tape).into()).unwrap();
+
+        assert_eq!(reel("camera one long name.mov"), "CAMERTG9");
+        assert_eq!(reel("Camera-One Long Name.mxf"), "CAMERJ6C");
+        assert_eq!(reel("camera one long name.mov"), "CAMERTG9");
+        assert_eq!(reel("cam2"), "CAM_B");
+        assert_eq!(reel("cam3"), "LONGREEL");
+        assert_eq!(reel("A001.mov"), "A001");
+        assert_eq!(reel("bars"), "BARSD94");
+        assert_eq!(reel("???"), "REEL07G");
+        // names shortening to a reel already in use are told apart by their hash
+        assert_eq!(reel("cam-1"), "CAM1");
+        assert_eq!(reel("cam_1"), "CAM145I");
+        assert_eq!(reel("CAM1.mxf"), "CAM1SNA");
+        assert_eq!(reel("cam-1"), "CAM1");
+        assert_eq!(reels.reel(&SourceTape::Bars).unwrap(), "BARS");
+        assert_eq!(reels.reel(&SourceTape::BL).unwrap(), "BL");
+
+        let mut reels = Reels::new("", 3).unwrap();
+        let mut reel = |tape: &str| reels.reel(&Some(tape).into()).unwrap();
+        assert_eq!(reel("camera one"), "CYL");
+        assert_eq!(reel("camera two"), "CWY");
+        assert_eq!(reel("???"), "R07");
+        // the same hash at three characters, the second moves on to the next
+        assert_eq!(reel("camera 17"), "CYT");
+        assert_eq!(reel("camera 80"), "CHU");
+        for (tape, reserved) in [
+            ("a x", "AX"),
+            ("b l", "BL"),
+            ("b a r", "BAR"),
+            ("a u x", "AUX"),
+        ] {
+            assert_ne!(reel(tape), reserved);
+        }
+        assert_eq!(reels.reel(&SourceTape::Bars).unwrap(), "BAR");
+        assert_eq!(reels.reel(&SourceTape::Aux).unwrap(), "AUX");
+
+        // explicit reel IDs are never handed out again, and can't clash with each other
+        let mut reels = Reels::new("a=cam1", 8).unwrap();
+        assert_eq!(reels.reel(&Some("cam-1").into()).unwrap(), "CAM1201");
+        assert_eq!(
+            Reels::new("a=CAM1\nb=cam1 ", 8).unwrap_err().to_string(),
+            "Reel ID 'cam1' for 'b' is already used by 'a'"
+        );
+        assert!(Reels::new("a=BL", 8).is_err());
+    }
+}
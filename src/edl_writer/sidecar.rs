@@ -0,0 +1,382 @@
+// machine readable log of each event as it's written, kept next to the EDL file as
+// newline delimited JSON and CSV. Unlike the EDL these keep full tape names and every
+// audio channel, plus where and when the request that wrote the event came from. Markers are
+// logged as they're dropped, rather than with the event they land in. Corrections don't change
+// what's already logged, the removed or patched event is logged again along with the events
+// rewritten with it. Each row names the EDL file its edit was written to, as edit numbers restart
+// in each rolled over part.
+
+use anyhow::{Context, Error};
+use serde::Serialize;
+use vtc::Timecode;
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    net::SocketAddr,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::edl_writer::{marker::Marker, Clip, EditType, Event};
+
+const CSV_HEADER: &str = "received_at_ms,client,ltc_frame,edit_number,edit_type,\
+edit_duration_frames,wipe_number,from_source_tape,source_tape,video,audio_channels,\
+source_in,source_out,record_in,record_out,marker_color,marker_name,marker_note,track,edl_file,correction";
+
+// the request an event was written in response to
+#[derive(Debug, Clone, Copy)]
+pub struct EventOrigin {
+    pub received_at: SystemTime,
+    pub client: Option<SocketAddr>,
+}
+
+impl EventOrigin {
+    pub fn new(client: Option<SocketAddr>) -> Self {
+        EventOrigin {
+            received_at: SystemTime::now(),
+            client,
+        }
+    }
+
+    fn received_at_ms(&self) -> u128 {
+        self.received_at
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_millis())
+            .unwrap_or_default()
+    }
+}
+
+// how an event logged again after a correction was changed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Correction {
+    Deleted,
+    Patched,
+    // renumbered or trimmed to fill the place of a deleted event
+    Rewritten,
+}
+
+impl From<Correction> for &str {
+    fn from(correction: Correction) -> Self {
+        match correction {
+            Correction::Deleted => "deleted",
+            Correction::Patched => "patched",
+            Correction::Rewritten => "rewritten",
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Logged<'a> {
+    Event(&'a Event),
+    Marker(&'a Marker),
+}
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    #[serde(flatten)]
+    logged: Logged<'a>,
+    track: &'a str,
+    edl_file: &'a str,
+    received_at_ms: u128,
+    client: Option<String>,
+    ltc_frame: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    correction: Option<Correction>,
+}
+
+#[derive(Debug)]
+pub struct Sidecar {
+    json: BufWriter<File>,
+    csv: BufWriter<File>,
+}
+
+impl Sidecar {
+    pub fn new(edl_path: &Path) -> Result<Self, Error> {
+        let json = File::create_new(edl_path.with_extension("json"))
+            .context("Could not create JSON sidecar file")?;
+        let mut csv = BufWriter::new(
+            File::create_new(edl_path.with_extension("csv"))
+                .context("Could not create CSV sidecar file")?,
+        );
+        writeln!(csv, "{CSV_HEADER}")?;
+        csv.flush()?;
+        Ok(Sidecar {
+            json: BufWriter::new(json),
+            csv,
+        })
+    }
+
+    pub fn append(
+        &mut self,
+        event: &Event,
+        track: &str,
+        edl_file: &str,
+        origin: &EventOrigin,
+        ltc_frame: Option<Timecode>,
+    ) -> Result<(), Error> {
+        let logged = Logged::Event(event);
+        self.write(logged, track, edl_file, origin, ltc_frame, None)
+    }
+
+    pub fn append_correction(
+        &mut self,
+        correction: Correction,
+        event: &Event,
+        track: &str,
+        edl_file: &str,
+        origin: &EventOrigin,
+    ) -> Result<(), Error> {
+        let logged = Logged::Event(event);
+        self.write(logged, track, edl_file, origin, None, Some(correction))
+    }
+
+    pub fn append_marker(
+        &mut self,
+        marker: &Marker,
+        track: &str,
+        edl_file: &str,
+        origin: &EventOrigin,
+        ltc_frame: Timecode,
+    ) -> Result<(), Error> {
+        let logged = Logged::Marker(marker);
+        self.write(logged, track, edl_file, origin, Some(ltc_frame), None)
+    }
+
+    fn write(
+        &mut self,
+        logged: Logged,
+        track: &str,
+        edl_file: &str,
+        origin: &EventOrigin,
+        ltc_frame: Option<Timecode>,
+        correction: Option<Correction>,
+    ) -> Result<(), Error> {
+        let entry = Entry {
+            logged,
+            track,
+            edl_file,
+            received_at_ms: origin.received_at_ms(),
+            client: origin.client.map(|client| client.to_string()),
+            ltc_frame: ltc_frame.map(|tc| tc.timecode()),
+            correction,
+        };
+        writeln!(self.json, "{}", serde_json::to_string(&entry)?)?;
+        self.json.flush()?;
+        writeln!(self.csv, "{}", csv_row(&entry))?;
+        self.csv.flush()?;
+        Ok(())
+    }
+}
+
+fn csv_row(entry: &Entry) -> String {
+    let event = match entry.logged {
+        Logged::Event(event) => event,
+        Logged::Marker(marker) => return csv_marker_row(entry, marker),
+    };
+    let mut clip: &Clip = event.into();
+    let (from, edit_duration_frames, wipe_number) = match event {
+        Event::Cut(_) => (None, None, None),
+        // written as a change from the background to the key, with the key in as its duration
+        Event::Key(key) => {
+            clip = &key.foreground;
+            (Some(&key.background), key.key_in_frames, None)
+        }
+        Event::Dissolve(dissolve) => (
+            Some(&dissolve.from),
+            Some(dissolve.edit_duration_frames),
+            None,
+        ),
+        Event::Wipe(wipe) => (
+            Some(&wipe.from),
+            Some(wipe.edit_duration_frames),
+            Some(wipe.wipe_number),
+        ),
+    };
+    let to_string = |n: Option<u32>| n.map(|n| n.to_string()).unwrap_or_default();
+    [
+        entry.received_at_ms.to_string(),
+        entry.client.clone().unwrap_or_default(),
+        entry.ltc_frame.clone().unwrap_or_default(),
+        clip.edit_number.to_string(),
+        <&str>::from(EditType::from(event)).into(),
+        to_string(edit_duration_frames),
+        to_string(wipe_number),
+        from.map(|from| <&str>::from(&from.source_tape).to_string())
+            .unwrap_or_default(),
+        <&str>::from(&clip.source_tape).into(),
+        clip.av_channels.video().to_string(),
+        clip.av_channels.audio().to_string(),
+        clip.source_in.timecode(),
+        clip.source_out.timecode(),
+        clip.record_in.timecode(),
+        clip.record_out.timecode(),
+        String::new(),
+        String::new(),
+        String::new(),
+        entry.track.into(),
+        entry.edl_file.into(),
+        entry
+            .correction
+            .map(|correction| <&str>::from(correction).into())
+            .unwrap_or_default(),
+    ]
+    .iter()
+    .map(|field| csv_field(field))
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+// a marker has no edit or source, its duration is written as the edit duration
+fn csv_marker_row(entry: &Entry, marker: &Marker) -> String {
+    let mut row = vec![
+        entry.received_at_ms.to_string(),
+        entry.client.clone().unwrap_or_default(),
+        entry.ltc_frame.clone().unwrap_or_default(),
+        String::new(),
+        "marker".into(),
+        marker
+            .duration_frames
+            .map(|n| n.to_string())
+            .unwrap_or_default(),
+    ];
+    row.resize(13, String::new());
+    row.extend([
+        marker.record.timecode(),
+        marker.record_out().timecode(),
+        <&str>::from(marker.color).into(),
+        marker.name.clone(),
+        marker.note.clone().unwrap_or_default(),
+        entry.track.into(),
+        entry.edl_file.into(),
+        String::new(),
+    ]);
+    row.iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::edl_writer::{marker::MarkerColor, AVChannels, Handles, Wipe};
+    use vtc::rates;
+
+    use std::{fs, path::PathBuf, time::Duration};
+
+    use crate::utils;
+
+    fn clip(tape: Option<&str>, av_channels: AVChannels, tc: (&str, &str)) -> Clip {
+        let tc_in = Timecode::with_frames(tc.0, rates::F24).unwrap();
+        let tc_out = Timecode::with_frames(tc.1, rates::F24).unwrap();
+        Clip {
+            edit_number: 2,
+            source_tape: tape.into(),
+            av_channels,
+            source_in: tc_in,
+            source_out: tc_out,
+            record_in: tc_in,
+            record_out: tc_out,
+            speed: None,
+            handles: Handles::default(),
+        }
+    }
+
+    #[test]
+    fn append_json_and_csv() {
+        let path = PathBuf::from("./test-output/sidecar");
+        fs::remove_dir_all(&path).ok();
+        let edl_path = utils::dirs::get_or_make_dir(path)
+            .unwrap()
+            .join("append_json_and_csv.edl");
+
+        let event = Event::Wipe(Wipe {
+            from: clip(
+                Some("cam1"),
+                AVChannels::default(),
+                ("01:00:00:00", "01:00:00:00"),
+            ),
+            to: clip(
+                Some("camera, two"),
+                AVChannels::new(true, 6),
+                ("01:00:00:00", "01:00:05:00"),
+            ),
+            wipe_number: 12,
+            edit_duration_frames: 10,
+        });
+        let origin = EventOrigin {
+            received_at: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+            client: Some("127.0.0.1:5000".parse().unwrap()),
+        };
+        let ltc_frame = Timecode::with_frames("01:00:05:00", rates::F24).ok();
+
+        let mut sidecar = Sidecar::new(&edl_path).unwrap();
+        let edl_file = "append_json_and_csv_part2.edl";
+        sidecar
+            .append(&event, "V1", edl_file, &origin, ltc_frame)
+            .unwrap();
+        sidecar
+            .append(&event, "V2", edl_file, &origin, ltc_frame)
+            .unwrap();
+
+        let json = fs::read_to_string(edl_path.with_extension("json")).unwrap();
+        let lines: Vec<serde_json::Value> = json
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["received_at_ms"], 1_700_000_000_123u64);
+        assert_eq!(lines[0]["client"], "127.0.0.1:5000");
+        assert_eq!(lines[0]["ltc_frame"], "01:00:05:00");
+        assert_eq!(lines[1]["track"], "V2");
+        assert_eq!(lines[1]["edl_file"], edl_file);
+        assert!(lines[0].get("correction").is_none());
+        assert_eq!(
+            lines[0]["event"]["wipe"]["to"]["source_tape"],
+            "camera, two"
+        );
+        assert_eq!(lines[0]["event"]["wipe"]["to"]["av_channels"]["audio"], 6);
+
+        let csv = fs::read_to_string(edl_path.with_extension("csv")).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], CSV_HEADER);
+        assert_eq!(
+            rows[1],
+            "1700000000123,127.0.0.1:5000,01:00:05:00,2,wipe,10,12,cam1,\"camera, two\",true,6,\
+             01:00:00:00,01:00:05:00,01:00:00:00,01:00:05:00,,,,V1,append_json_and_csv_part2.edl,"
+        );
+
+        let marker = Marker {
+            record: Timecode::with_frames("01:00:03:00", rates::F24).unwrap(),
+            color: MarkerColor::Yellow,
+            name: "audio issue".into(),
+            note: Some("mic 2, crackles".into()),
+            duration_frames: Some(24),
+        };
+        sidecar
+            .append_marker(&marker, "V1", edl_file, &origin, marker.record)
+            .unwrap();
+        let json = fs::read_to_string(edl_path.with_extension("json")).unwrap();
+        let line: serde_json::Value = serde_json::from_str(json.lines().last().unwrap()).unwrap();
+        assert_eq!(line["marker"]["color"], "YELLOW");
+        assert_eq!(line["marker"]["record"], "01:00:03:00");
+        let csv = fs::read_to_string(edl_path.with_extension("csv")).unwrap();
+        assert_eq!(
+            csv.lines().last().unwrap(),
+            "1700000000123,127.0.0.1:5000,01:00:03:00,,marker,24,,,,,,,,\
+             01:00:03:00,01:00:04:00,YELLOW,audio issue,\"mic 2, crackles\",V1,\
+             append_json_and_csv_part2.edl,"
+        );
+    }
+}
@@ -0,0 +1,223 @@
+// Source timecodes for ISO recorders that don't share the LTC. A tape can be given the timecode
+// its recorder was at on the first edit of the EDL, or a constant offset from the LTC. Source in
+// and out points of that tape are translated into the recorder's timecode, while record in and
+// out points stay on the LTC.
+
+use anyhow::{anyhow, Error};
+use vtc::{Framerate, Timecode};
+
+use crate::{
+    edl_writer::{midnight, reel::parse_tape_pairs, Event, SourceTape},
+    ltc_decoder::TCError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SourceTc {
+    // the recorder's timecode on the first edit of the EDL
+    Start(Timecode),
+    // frames added to the LTC, negative for a recorder behind it
+    Offset(i64),
+}
+
+impl SourceTc {
+    // `10:00:00:00` for a start timecode, `+00:00:01:00` or `-00:00:01:00` for an offset
+    pub fn parse(value: &str, rate: Framerate) -> Result<Self, Error> {
+        let value = value.trim();
+        let timecode = |tc: &str| {
+            Timecode::with_frames(tc.trim(), rate)
+                .map_err(|e| anyhow!("Invalid source timecode '{}': {}", value, e.into_msg()))
+        };
+        match value.split_at_checked(1) {
+            Some(("+", tc)) => Ok(SourceTc::Offset(timecode(tc)?.frames())),
+            Some(("-", tc)) => Ok(SourceTc::Offset(-timecode(tc)?.frames())),
+            _ => Ok(SourceTc::Start(timecode(value)?)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SourceTimecodes {
+    tapes: Vec<(String, SourceTc)>,
+    // the LTC of the first edit, which start timecodes count from
+    record_start: Option<Timecode>,
+}
+
+impl SourceTimecodes {
+    // `source_timecodes` holds one `tape=timecode` per line
+    pub fn new(source_timecodes: &str, rate: Framerate) -> Self {
+        let tapes = parse_tape_pairs(source_timecodes)
+            .into_iter()
+            .filter_map(|(tape, value)| {
+                SourceTc::parse(&value, rate)
+                    .inspect_err(|e| log::warn!("{e} for '{tape}'"))
+                    .ok()
+                    .map(|source_tc| (tape, source_tc))
+            })
+            .collect();
+        SourceTimecodes {
+            tapes,
+            record_start: None,
+        }
+    }
+
+    // `None` puts the tape back on the LTC
+    pub fn set(&mut self, tape: &str, source_tc: Option<SourceTc>) {
+        self.tapes.retain(|(name, _)| name != tape);
+        if let Some(source_tc) = source_tc {
+            self.tapes.push((tape.into(), source_tc));
+        }
+    }
+
+    pub fn start(&mut self, record_start: Timecode) {
+        self.record_start.get_or_insert(record_start);
+    }
+
+    pub fn apply(&self, event: &mut Event) -> Result<(), Error> {
+        for clip in event.clips_mut() {
+            let Some(mut offset) = self.offset(&clip.source_tape) else {
+                continue;
+            };
+            // a recorder behind the LTC can still be on the day before
+            let day = midnight::day_frames(clip.source_in.rate());
+            if clip.source_in.frames() + offset < 0 {
+                offset += day;
+            }
+            let shift = |tc: Timecode| {
+                Timecode::with_frames(tc.frames() + offset, tc.rate()).map_err(|e| {
+                    anyhow!(
+                        "Source timecode for '{}' is out of range: {}",
+                        <&str>::from(&clip.source_tape),
+                        e.into_msg()
+                    )
+                })
+            };
+            let (source_in, source_out) = (shift(clip.source_in)?, shift(clip.source_out)?);
+            clip.source_in = source_in;
+            clip.source_out = source_out;
+            // the recorder has nothing before its start timecode to add as a head handle
+            if let Some(start) = self.source_start(&clip.source_tape) {
+                let recorded = (clip.source_in.frames() - start.frames()).max(0);
+                clip.handles.head = clip.handles.head.min(recorded as u32);
+            }
+        }
+        Ok(())
+    }
+
+    // the tape's source timecode on the first edit of the EDL, where its recorder started and
+    // frame 0 of its media. Tapes on the LTC start at the LTC of the first edit.
+    pub fn media_start(&self, tape: &str) -> Option<Timecode> {
+        let record_start = self.record_start?;
+        let offset = self
+            .offset(&SourceTape::AX(tape.into()))
+            .unwrap_or_default();
+        Timecode::with_frames(record_start.frames() + offset, record_start.rate()).ok()
+    }
+
+    fn source_start(&self, source_tape: &SourceTape) -> Option<Timecode> {
+        let SourceTape::AX(tape) = source_tape else {
+            return None;
+        };
+        match self.tapes.iter().find(|(name, _)| name == tape)?.1 {
+            SourceTc::Start(start) => Some(start),
+            SourceTc::Offset(_) => None,
+        }
+    }
+
+    fn offset(&self, source_tape: &SourceTape) -> Option<i64> {
+        let SourceTape::AX(tape) = source_tape else {
+            return None;
+        };
+        match self.tapes.iter().find(|(name, _)| name == tape)?.1 {
+            SourceTc::Offset(frames) => Some(frames),
+            SourceTc::Start(start) => Some(start.frames() - self.record_start?.frames()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        edl_writer::{AVChannels, Clip, Handles},
+        export::test::clip,
+    };
+    use vtc::rates;
+
+    #[test]
+    fn translate_source_timecodes() {
+        let rate = rates::F24;
+        let tc = |tc: &str| Timecode::with_frames(tc, rate).unwrap();
+        let mut source_tcs = SourceTimecodes::new(
+            "iso1=10:00:00:00\niso2 = -00:00:01:00\niso3=+00:00:00:12\niso4=not a timecode",
+            rate,
+        );
+        source_tcs.start(tc("01:00:00:00"));
+        source_tcs.start(tc("02:00:00:00"));
+        let translated = |source_tcs: &SourceTimecodes, tape: &str| {
+            let mut event = Event::Cut(clip(
+                1,
+                Some(tape),
+                AVChannels::video_only(),
+                ("01:00:10:00", "01:00:20:00"),
+                rate,
+            ));
+            source_tcs.apply(&mut event).unwrap();
+            let clip: &crate::edl_writer::Clip = (&event).into();
+            assert_eq!(clip.record_in, tc("01:00:10:00"));
+            format!(
+                "{} {}",
+                clip.source_in.timecode(),
+                clip.source_out.timecode()
+            )
+        };
+
+        assert_eq!(translated(&source_tcs, "iso1"), "10:00:10:00 10:00:20:00");
+        // nothing was recorded before the start timecode to add as a head handle, and clips are
+        // left where they were logged
+        let handled = |source_tcs: &SourceTimecodes, tape: &str, tc_in: &str| {
+            let mut event = Event::Cut(Clip {
+                handles: Handles { head: 48, tail: 0 },
+                ..clip(
+                    1,
+                    Some(tape),
+                    AVChannels::video_only(),
+                    (tc_in, "01:00:20:00"),
+                    rate,
+                )
+            });
+            source_tcs.apply(&mut event).unwrap();
+            let clip: &Clip = (&event).into();
+            format!("{} {}", clip.source_in.timecode(), clip.handles.head)
+        };
+        assert_eq!(
+            handled(&source_tcs, "iso1", "01:00:01:00"),
+            "10:00:01:00 24"
+        );
+        assert_eq!(handled(&source_tcs, "iso1", "00:59:58:00"), "09:59:58:00 0");
+        assert_eq!(
+            handled(&source_tcs, "iso3", "01:00:01:00"),
+            "01:00:01:12 48"
+        );
+
+        // a recorder behind the LTC is still on the day before just after midnight
+        let mut event = Event::Cut(clip(
+            1,
+            Some("iso2"),
+            AVChannels::video_only(),
+            ("00:00:00:12", "00:00:10:00"),
+            rate,
+        ));
+        source_tcs.apply(&mut event).unwrap();
+        let clip: &Clip = (&event).into();
+        assert_eq!(midnight::wrap(clip.source_in).timecode(), "23:59:59:12");
+        assert_eq!(clip.source_out.frames() - clip.source_in.frames(), 228);
+        assert_eq!(translated(&source_tcs, "iso2"), "01:00:09:00 01:00:19:00");
+        assert_eq!(translated(&source_tcs, "iso3"), "01:00:10:12 01:00:20:12");
+        assert_eq!(translated(&source_tcs, "iso4"), "01:00:10:00 01:00:20:00");
+
+        source_tcs.set("iso1", None);
+        source_tcs.set("iso4", Some(SourceTc::parse("20:00:00:00", rate).unwrap()));
+        assert_eq!(translated(&source_tcs, "iso1"), "01:00:10:00 01:00:20:00");
+        assert_eq!(translated(&source_tcs, "iso4"), "20:00:10:00 20:00:20:00");
+    }
+}
@@ -0,0 +1,205 @@
+// AES31-3 Audio Decision List, as read by Pro Tools (via AATranslator) and Pyramix.
+// https://www.aes.org/publications/standards/search.cfm?docID=15
+//
+// Each edit with audio becomes one entry per channel. Positions are written as
+// `HH?MM?SS?FF.SSSS`, a timecode followed by a count of samples into the frame at the
+// configured sample rate, with the separator marking the frame rate. Edits land on frame
+// boundaries so the sample count is always zero, but readers place the entry to the sample.
+
+use anyhow::{Context, Error};
+use vtc::Timecode;
+
+use std::fmt::Write;
+
+use crate::{
+    edl_writer::{Clip, Event, SourceTape},
+    export::{Rate, Session},
+};
+
+pub fn render(session: &Session) -> Result<String, Error> {
+    let start = session.start().context("Session has no edits to export")?;
+    let rate = session.rate().context("Session has no edits to export")?;
+    let tapes = tapes(session);
+    let position = |frames: i64| position(frames, rate);
+
+    let mut adl = String::new();
+    writeln!(adl, "<ADL>")?;
+    writeln!(adl, "\t<VERSION>")?;
+    writeln!(adl, "\t\t(VER_ADL_VERSION)\t01.01")?;
+    writeln!(adl, "\t\t(VER_CREATOR)\t\"edl-gen\"")?;
+    writeln!(adl, "\t\t(VER_CRTR)\t{}", env!("CARGO_PKG_VERSION"))?;
+    writeln!(adl, "\t</VERSION>")?;
+    writeln!(adl, "\t<PROJECT>")?;
+    writeln!(adl, "\t\t(PROJ_TITLE)\t\"{}\"", quote(session.title))?;
+    writeln!(adl, "\t\t(PROJ_ORIGINATOR)\t\"edl-gen\"")?;
+    writeln!(adl, "\t</PROJECT>")?;
+    writeln!(adl, "\t<SYSTEM>")?;
+    writeln!(adl, "\t\t(SYS_FPS)\t{}", rate.timebase())?;
+    writeln!(adl, "\t\t(SYS_SRATE)\t{}", session.opt.sample_rate)?;
+    writeln!(adl, "\t</SYSTEM>")?;
+    writeln!(adl, "\t<SEQUENCE>")?;
+    writeln!(adl, "\t\t(SEQ_SAMPLE_RATE)\tS{}", session.opt.sample_rate)?;
+    writeln!(adl, "\t\t(SEQ_FRAME_RATE)\t{}", rate.timebase())?;
+    writeln!(adl, "\t\t(SEQ_ADL_LEVEL)\t1")?;
+    writeln!(adl, "\t\t(SEQ_CLEAN_LIST)\t1")?;
+    writeln!(adl, "\t\t(SEQ_SORT)\t1")?;
+    writeln!(adl, "\t\t(SEQ_MULTICHAN)\t0")?;
+    writeln!(adl, "\t\t(SEQ_DEST_START)\t{}", position(start.frames()))?;
+    writeln!(adl, "\t</SEQUENCE>")?;
+
+    writeln!(adl, "\t<SOURCE_INDEX>")?;
+    for (i, tape) in tapes.iter().enumerate() {
+        writeln!(
+            adl,
+            "\t\t(Index)\t{:04}\t(F)\t\"URL:file://localhost/{}\"\tN\t{}\t{}\t\"{}\"\tN",
+            i + 1,
+            quote(tape.name),
+            position(tape.start.frames()),
+            position(tape.end.frames() - tape.start.frames()),
+            quote(tape.name),
+        )?;
+    }
+    writeln!(adl, "\t</SOURCE_INDEX>")?;
+
+    writeln!(adl, "\t<EVENT_LIST>")?;
+    let mut entry_count = 0;
+    for (i, event) in session.events.iter().enumerate() {
+        let clip: &Clip = event.into();
+        let SourceTape::AX(name) = &clip.source_tape else {
+            continue;
+        };
+        let index = tapes
+            .iter()
+            .position(|tape| tape.name == name)
+            .unwrap_or_default();
+        // CMX3600 dissolves start on the edit point, so the outgoing clip plays on under
+        // the incoming one for the length of the crossfade
+        let fade_in = crossfade(event);
+        let fade_out = session.events.get(i + 1).and_then(crossfade);
+        let record_out = clip.record_out.frames() + fade_out.unwrap_or_default();
+        for channel in 1..=clip.av_channels.audio() {
+            entry_count += 1;
+            writeln!(
+                adl,
+                "\t\t(Entry)\t{:04}\t(Cut)\tI\t{:04}\t{}\t{}\t{}\t{}\t{}\t_",
+                entry_count,
+                index + 1,
+                channel,
+                channel,
+                position(clip.source_in.frames()),
+                position(clip.record_in.frames()),
+                position(record_out),
+            )?;
+            if let Some(frames) = fade_in {
+                writeln!(adl, "\t\t(Infade)\t{}\tLIN\t_\t_\t_", position(frames))?;
+            }
+            if let Some(frames) = fade_out {
+                writeln!(adl, "\t\t(Outfade)\t{}\tLIN\t_\t_\t_", position(frames))?;
+            }
+        }
+    }
+    writeln!(adl, "\t</EVENT_LIST>")?;
+    writeln!(adl, "</ADL>")?;
+    Ok(adl)
+}
+
+// wipes are video only, so only dissolves crossfade the audio
+fn crossfade(event: &Event) -> Option<i64> {
+    match event {
+        Event::Dissolve(dissolve) => Some(dissolve.edit_duration_frames as i64),
+        _ => None,
+    }
+}
+
+// AES31-3 marks the frame rate with the timecode separator: '=' for 24, '|' for 25,
+// ':' for 30 and ';' for 30 drop frame.
+fn position(frames: i64, rate: Rate) -> String {
+    let separator = match (rate.timebase(), rate.is_drop_frame()) {
+        (24, _) => '=',
+        (25, _) => '|',
+        (_, true) => ';',
+        _ => ':',
+    };
+    let tc = Timecode::with_frames(frames.max(0), rate.0)
+        .map(|tc| tc.timecode())
+        .unwrap_or_else(|_| "00:00:00:00".into());
+    let tc: String = tc
+        .chars()
+        .map(|c| match c {
+            ':' | ';' => separator,
+            c => c,
+        })
+        .collect();
+    format!("{tc}.0000")
+}
+
+fn quote(value: &str) -> String {
+    value.replace('"', "'")
+}
+
+struct Tape<'a> {
+    name: &'a str,
+    start: Timecode,
+    end: Timecode,
+}
+
+// one source index entry per tape with audio, spanning every range used from it
+fn tapes<'a>(session: &Session<'a>) -> Vec<Tape<'a>> {
+    let mut tapes: Vec<Tape> = Vec::new();
+    for clip in session.clips() {
+        let SourceTape::AX(name) = &clip.source_tape else {
+            continue;
+        };
+        if clip.av_channels.audio() == 0 {
+            continue;
+        }
+        match tapes.iter_mut().find(|tape| tape.name == name) {
+            Some(tape) => {
+                tape.start = tape.start.min(clip.source_in);
+                tape.end = tape.end.max(clip.source_out);
+            }
+            None => tapes.push(Tape {
+                name,
+                start: clip.source_in,
+                end: clip.source_out,
+            }),
+        }
+    }
+    tapes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::{test::session_events, ExportOpt};
+    use vtc::{rates, Framerate, Ntsc};
+
+    #[test]
+    fn adl_audio_events() {
+        let events = session_events(Framerate::with_playback(25, Ntsc::None).unwrap());
+        let adl = render(&Session::new("my show", &events, &ExportOpt::default())).unwrap();
+
+        assert!(adl.contains("(SEQ_SAMPLE_RATE)\tS48000"));
+        assert!(adl.contains("(SEQ_DEST_START)\t01|00|00|00.0000"));
+        assert_eq!(adl.matches("(Index)").count(), 1);
+        assert!(adl.contains(
+            "(Index)\t0001\t(F)\t\"URL:file://localhost/camera one long name.mov\"\tN\t\
+             01|00|00|00.0000\t00|00|30|00.0000\t\"camera one long name.mov\"\tN"
+        ));
+        // two channels of the first cut, the video only wipe is left out, then four channels
+        // crossfading back in
+        assert_eq!(adl.matches("(Entry)").count(), 6);
+        assert!(adl.contains(
+            "(Entry)\t0002\t(Cut)\tI\t0001\t2\t2\t\
+             01|00|00|00.0000\t01|00|00|00.0000\t01|00|10|00.0000\t_"
+        ));
+        assert!(adl.contains(
+            "(Entry)\t0006\t(Cut)\tI\t0001\t4\t4\t\
+             01|00|20|00.0000\t01|00|20|00.0000\t01|00|30|00.0000\t_\n\
+             \t\t(Infade)\t00|00|00|12.0000\tLIN\t_\t_\t_"
+        ));
+        assert_eq!(adl.matches("(Outfade)").count(), 0);
+        assert_eq!(position(0, Rate(rates::F24)), "00=00=00=00.0000");
+        assert_eq!(position(1, Rate(rates::F29_97_DF)), "00;00;00;01.0000");
+    }
+}
@@ -0,0 +1,180 @@
+// Avid Log Exchange, for batch linking iso media in Media Composer before importing the EDL.
+// Lists a master clip per source tape spanning every range used from it, followed by a subclip
+// for each edit taken from that tape.
+
+use anyhow::{Context, Error};
+use serde::Deserialize;
+
+use crate::{
+    edl_writer::{AVChannels, Clip, EditType, SourceTape},
+    export::{Rate, Session},
+};
+
+const COLUMNS: [&str; 7] = [
+    "Name",
+    "Tape",
+    "Start",
+    "End",
+    "Tracks",
+    "Edit Number",
+    "Edit Type",
+];
+
+pub fn render(session: &Session) -> Result<String, Error> {
+    let rate = session.rate().context("Session has no edits to export")?;
+    let mut tapes: Vec<Tape> = Vec::new();
+    for event in session.events {
+        let clip: &Clip = event.into();
+        if !matches!(clip.source_tape, SourceTape::AX(_)) || clip.source_in == clip.source_out {
+            continue;
+        }
+        let subclip = Subclip::new(clip, event.into())?;
+        match tapes
+            .iter_mut()
+            .find(|tape| tape.name == subclip.clip.source_tape)
+        {
+            Some(tape) => tape.add(clip, subclip),
+            None => tapes.push(Tape::new(clip, subclip)),
+        }
+    }
+
+    let mut ale = format!(
+        "Heading\nFIELD_DELIM\tTABS\nVIDEO_FORMAT\t1080\nFPS\t{}\n\nColumn\n{}\n\nData\n",
+        fps(rate),
+        COLUMNS.join("\t")
+    );
+    for tape in tapes.iter() {
+        ale.push_str(&row(&[
+            &tape.name,
+            &tape.name,
+            &tape.start.timecode(),
+            &tape.end.timecode(),
+            &tracks(tape.av_channels),
+            "",
+            "",
+        ]));
+        for Subclip { clip, edit_type } in tape.subclips.iter() {
+            ale.push_str(&row(&[
+                &format!("{} {:03}", clip.source_tape, clip.edit_number),
+                &clip.source_tape,
+                &clip.source_in,
+                &clip.source_out,
+                &tracks(clip.av_channels),
+                &format!("{:03}", clip.edit_number),
+                (*edit_type).into(),
+            ]));
+        }
+    }
+    Ok(ale)
+}
+
+struct Subclip {
+    clip: ClipColumns,
+    edit_type: EditType,
+}
+
+// the columns of a clip as it's serialized for the server response
+#[derive(Deserialize)]
+struct ClipColumns {
+    edit_number: usize,
+    source_tape: String,
+    av_channels: AVChannels,
+    source_in: String,
+    source_out: String,
+}
+
+impl Subclip {
+    fn new(clip: &Clip, edit_type: EditType) -> Result<Self, Error> {
+        Ok(Subclip {
+            clip: serde_json::from_value(serde_json::to_value(clip)?)?,
+            edit_type,
+        })
+    }
+}
+
+struct Tape {
+    name: String,
+    av_channels: AVChannels,
+    start: vtc::Timecode,
+    end: vtc::Timecode,
+    subclips: Vec<Subclip>,
+}
+
+impl Tape {
+    fn new(clip: &Clip, subclip: Subclip) -> Self {
+        Tape {
+            name: subclip.clip.source_tape.clone(),
+            av_channels: clip.av_channels,
+            start: clip.source_in,
+            end: clip.source_out,
+            subclips: vec![subclip],
+        }
+    }
+
+    fn add(&mut self, clip: &Clip, subclip: Subclip) {
+        self.av_channels = AVChannels::new(
+            self.av_channels.video() || clip.av_channels.video(),
+            self.av_channels.audio().max(clip.av_channels.audio()),
+        );
+        self.start = self.start.min(clip.source_in);
+        self.end = self.end.max(clip.source_out);
+        self.subclips.push(subclip);
+    }
+}
+
+fn row(values: &[&str]) -> String {
+    // tabs and newlines would break the column layout
+    let values: Vec<String> = values
+        .iter()
+        .map(|value| value.replace(['\t', '\n', '\r'], " "))
+        .collect();
+    format!("{}\n", values.join("\t"))
+}
+
+// ALE track names, ie. "VA1A2"
+fn tracks(av_channels: AVChannels) -> String {
+    (1..=av_channels.audio()).fold(
+        if av_channels.video() { "V" } else { "" }.to_string(),
+        |acc, channel| format!("{acc}A{channel}"),
+    )
+}
+
+fn fps(rate: Rate) -> String {
+    format!("{:.3}", rate.fps())
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::{test::session_events, ExportOpt};
+    use vtc::rates;
+
+    #[test]
+    fn ale_sources_and_subclips() {
+        let events = session_events(rates::F29_97_NDF);
+        let ale = render(&Session::new("my show", &events, &ExportOpt::default())).unwrap();
+        let lines: Vec<&str> = ale.lines().collect();
+
+        assert_eq!(lines[0], "Heading");
+        assert_eq!(lines[3], "FPS\t29.97");
+        assert_eq!(
+            lines[6],
+            "Name\tTape\tStart\tEnd\tTracks\tEdit Number\tEdit Type"
+        );
+        assert_eq!(
+            &lines[9..],
+            [
+                "camera one long name.mov\tcamera one long name.mov\t01:00:00:00\t01:00:30:00\tVA1A2A3A4\t\t",
+                "camera one long name.mov 001\tcamera one long name.mov\t01:00:00:00\t01:00:10:00\tVA1A2\t001\tcut",
+                "camera one long name.mov 003\tcamera one long name.mov\t01:00:20:00\t01:00:30:00\tVA1A2A3A4\t003\tdissolve",
+                "cam2\tcam2\t01:00:10:00\t01:00:20:00\tV\t\t",
+                "cam2 002\tcam2\t01:00:10:00\t01:00:20:00\tV\t002\twipe",
+            ]
+        );
+        assert_eq!(fps(Rate(rates::F23_98)), "23.976");
+        assert_eq!(fps(Rate(rates::F24)), "24");
+    }
+}
@@ -0,0 +1,184 @@
+// Chapter lists for livestream VOD uploads: SRT subtitles, WebVTT chapters and YouTube style
+// `00:00 Title` descriptions.
+// https://www.w3.org/TR/webvtt1/#introduction-chapters
+//
+// A chapter starts at each change of source, titled with the source's label or tape name.
+// Times are relative to the first record in point, and chapters shorter than the configured
+// minimum are folded into the one before them.
+
+use anyhow::{Context, Error};
+
+use crate::{
+    edl_writer::{Clip, SourceTape},
+    export::Session,
+};
+
+pub fn render_srt(session: &Session) -> Result<String, Error> {
+    let chapters = chapters(session)?;
+    Ok(chapters
+        .iter()
+        .enumerate()
+        .map(|(i, chapter)| {
+            format!(
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                timestamp(chapter.start_ms, ','),
+                timestamp(chapter.end_ms, ','),
+                chapter.title
+            )
+        })
+        .collect())
+}
+
+pub fn render_vtt(session: &Session) -> Result<String, Error> {
+    let chapters = chapters(session)?;
+    let cues: String = chapters
+        .iter()
+        .enumerate()
+        .map(|(i, chapter)| {
+            format!(
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                timestamp(chapter.start_ms, '.'),
+                timestamp(chapter.end_ms, '.'),
+                // cue text can't hold the timing arrow
+                chapter.title.replace("-->", "->")
+            )
+        })
+        .collect();
+    Ok(format!("WEBVTT\n\n{cues}"))
+}
+
+pub fn render_youtube(session: &Session) -> Result<String, Error> {
+    let chapters = chapters(session)?;
+    let hours = chapters.last().is_some_and(|c| c.start_ms >= 3_600_000);
+    Ok(chapters
+        .iter()
+        .map(|chapter| {
+            let secs = chapter.start_ms / 1000;
+            let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+            match hours {
+                true => format!("{h}:{m:02}:{s:02} {}\n", chapter.title),
+                false => format!("{m:02}:{s:02} {}\n", chapter.title),
+            }
+        })
+        .collect())
+}
+
+#[derive(Debug, PartialEq)]
+struct Chapter {
+    title: String,
+    start_ms: i64,
+    end_ms: i64,
+}
+
+fn chapters(session: &Session) -> Result<Vec<Chapter>, Error> {
+    let start = session.start().context("Session has no edits to export")?;
+    let end = session.end().context("Session has no edits to export")?;
+    let rate = session.rate().context("Session has no edits to export")?;
+    let (num, den) = rate.frame_duration();
+    let ms = |frames: i64| frames * num * 1000 / den;
+    let min_ms = session.opt.chapter_min_secs as i64 * 1000;
+
+    // black, bars and aux don't start a chapter of their own, they run on from the shot before
+    let starts: Vec<(&str, i64)> = session
+        .clips()
+        .filter_map(|clip: &Clip| match &clip.source_tape {
+            SourceTape::AX(name) => Some((
+                session.opt.label(name),
+                ms(clip.record_in.frames() - start.frames()),
+            )),
+            _ => None,
+        })
+        .collect();
+    let session_end = ms(end.frames() - start.frames());
+
+    let mut chapters: Vec<Chapter> = Vec::new();
+    for (i, (title, start_ms)) in starts.iter().enumerate() {
+        let end_ms = starts.get(i + 1).map_or(session_end, |(_, next)| *next);
+        if end_ms - start_ms < min_ms && !chapters.is_empty() {
+            continue;
+        }
+        match chapters.last_mut() {
+            Some(last) if last.title == *title => (),
+            _ => chapters.push(Chapter {
+                title: title.to_string(),
+                start_ms: *start_ms,
+                end_ms,
+            }),
+        }
+    }
+    // the first chapter always opens the video, and each runs up to the next
+    if let Some(first) = chapters.first_mut() {
+        first.start_ms = 0;
+    }
+    for i in 1..chapters.len() {
+        chapters[i - 1].end_ms = chapters[i].start_ms;
+    }
+    if let Some(last) = chapters.last_mut() {
+        last.end_ms = session_end;
+    }
+    Ok(chapters)
+}
+
+fn timestamp(ms: i64, decimal: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{decimal}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::{test::session_events, ExportOpt};
+    use vtc::rates;
+
+    #[test]
+    fn chapter_lists() {
+        let events = session_events(rates::F24);
+        let opt = ExportOpt {
+            source_labels: vec![("cam2".into(), "Guest".into())],
+            ..ExportOpt::default()
+        };
+        let session = Session::new("my show", &events, &opt);
+
+        assert_eq!(
+            render_srt(&session).unwrap(),
+            "1\n00:00:00,000 --> 00:00:10,000\ncamera one long name.mov\n\n\
+             2\n00:00:10,000 --> 00:00:20,000\nGuest\n\n\
+             3\n00:00:20,000 --> 00:00:30,000\ncamera one long name.mov\n\n"
+        );
+        assert!(render_vtt(&session)
+            .unwrap()
+            .starts_with("WEBVTT\n\n1\n00:00:00.000 --> 00:00:10.000\n"));
+        assert_eq!(
+            render_youtube(&session).unwrap(),
+            "00:00 camera one long name.mov\n00:10 Guest\n00:20 camera one long name.mov\n"
+        );
+    }
+
+    #[test]
+    fn short_chapters_dropped() {
+        let events = session_events(rates::F29_97_NDF);
+        let opt = ExportOpt {
+            chapter_min_secs: 11,
+            ..ExportOpt::default()
+        };
+        let chapters = chapters(&Session::new("my show", &events, &opt)).unwrap();
+
+        // every shot is ten seconds, but the first chapter is always kept and the rest fold
+        // into it
+        assert_eq!(
+            chapters,
+            vec![Chapter {
+                title: "camera one long name.mov".into(),
+                start_ms: 0,
+                end_ms: 30_030,
+            }]
+        );
+    }
+}
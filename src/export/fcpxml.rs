@@ -0,0 +1,262 @@
+// FCPXML 1.10, as read by Final Cut Pro and DaVinci Resolve.
+// https://developer.apple.com/documentation/professional_video_applications/fcpxml_reference
+
+use anyhow::{Context, Error};
+use vtc::Timecode;
+
+use crate::{
+    edl_writer::{Event, SourceTape},
+    export::{xml::Xml, Rate, Session, Transition},
+};
+
+const FORMAT_ID: &str = "r1";
+
+pub fn render(session: &Session) -> Result<String, Error> {
+    let start = session.start().context("Session has no edits to export")?;
+    let end = session.end().context("Session has no edits to export")?;
+    let rate = session.rate().context("Session has no edits to export")?;
+    let assets = Assets::new(session);
+    let dissolve_id = format!("r{}", assets.0.len() + 2);
+    let tc_format = if rate.is_drop_frame() { "DF" } else { "NDF" };
+    let (num, den) = rate.frame_duration();
+
+    let mut xml = Xml::new();
+    xml.doctype("fcpxml")
+        .open("fcpxml", &[("version", "1.10")])
+        .open("resources", &[])
+        .empty(
+            "format",
+            &[
+                ("id", FORMAT_ID),
+                ("frameDuration", &format!("{num}/{den}s")),
+            ],
+        );
+    assets.write(&mut xml, rate);
+    xml.empty(
+        "effect",
+        &[
+            ("id", &dissolve_id),
+            ("name", "Cross Dissolve"),
+            ("uid", "FxPlug:4731E73A-8DAC-4113-9A30-AE85B1761265"),
+        ],
+    )
+    .close()
+    .open("library", &[])
+    .open("event", &[("name", session.title)])
+    .open("project", &[("name", session.title)])
+    .open(
+        "sequence",
+        &[
+            ("format", FORMAT_ID),
+            ("duration", &time(end.frames() - start.frames(), rate)),
+            ("tcStart", &time(start.frames(), rate)),
+            ("tcFormat", tc_format),
+        ],
+    )
+    .open("spine", &[]);
+
+    let mut prev_clip = false;
+    for item in session.track() {
+        if item.gap > 0 {
+            write_gap(&mut xml, item.record_in - item.gap, item.gap, rate);
+            prev_clip = false;
+        }
+        let is_clip = matches!(item.clip.source_tape, SourceTape::AX(_));
+        if is_clip || prev_clip {
+            write_transition(&mut xml, item.event, &dissolve_id, rate);
+        }
+        match &item.clip.source_tape {
+            SourceTape::AX(name) => {
+                let mut attrs = vec![
+                    ("ref", assets.id(name)),
+                    ("name", name.clone()),
+                    ("offset", time(item.record_in, rate)),
+                    ("start", time(item.source_in, rate)),
+                    ("duration", time(item.duration, rate)),
+                    ("tcFormat", tc_format.into()),
+                ];
+                match (item.clip.av_channels.video(), item.clip.av_channels.audio()) {
+                    (false, _) => attrs.push(("srcEnable", "audio".into())),
+                    (true, 0) => attrs.push(("srcEnable", "video".into())),
+                    _ => (),
+                };
+                xml.empty("asset-clip", &borrow_attrs(&attrs));
+            }
+            // black, bars and aux have no media to reference
+            _ => write_gap(&mut xml, item.record_in, item.duration, rate),
+        }
+        prev_clip = is_clip;
+    }
+
+    Ok(xml.finish())
+}
+
+fn write_transition(xml: &mut Xml, event: &Event, dissolve_id: &str, rate: Rate) {
+    let Some(transition) = Transition::from_event(event) else {
+        return;
+    };
+    let name = match transition.wipe_number {
+        Some(wipe_number) => format!("Wipe {:03}", wipe_number),
+        None => "Cross Dissolve".into(),
+    };
+    xml.open(
+        "transition",
+        &[
+            ("name", &name),
+            ("offset", &time(transition.to.record_in.frames(), rate)),
+            ("duration", &time(transition.duration as i64, rate)),
+        ],
+    );
+    if transition.wipe_number.is_none() {
+        xml.empty(
+            "filter-video",
+            &[("ref", dissolve_id), ("name", "Cross Dissolve")],
+        );
+    }
+    xml.close();
+}
+
+fn write_gap(xml: &mut Xml, offset: i64, duration: i64, rate: Rate) {
+    xml.empty(
+        "gap",
+        &[
+            ("name", "Gap"),
+            ("offset", &time(offset, rate)),
+            ("duration", &time(duration, rate)),
+        ],
+    );
+}
+
+// a frame count as rational seconds, ie. "1001/24000s" for a single frame at 23.976
+fn time(frames: i64, rate: Rate) -> String {
+    let (num, den) = rate.frame_duration();
+    match frames {
+        0 => "0s".into(),
+        n => format!("{}/{}s", n * num, den),
+    }
+}
+
+fn borrow_attrs<'a>(attrs: &'a [(&'static str, String)]) -> Vec<(&'static str, &'a str)> {
+    attrs
+        .iter()
+        .map(|(key, value)| (*key, value.as_str()))
+        .collect()
+}
+
+// one asset per source tape, spanning every range used from it
+struct Assets<'a>(Vec<Asset<'a>>);
+
+struct Asset<'a> {
+    name: &'a str,
+    video: bool,
+    audio: u8,
+    start: Timecode,
+    end: Timecode,
+}
+
+impl<'a> Assets<'a> {
+    fn new(session: &Session<'a>) -> Self {
+        let mut assets: Vec<Asset> = Vec::new();
+        for clip in session.clips() {
+            let SourceTape::AX(name) = &clip.source_tape else {
+                continue;
+            };
+            match assets.iter_mut().find(|asset| asset.name == name) {
+                Some(asset) => {
+                    asset.video |= clip.av_channels.video();
+                    asset.audio = asset.audio.max(clip.av_channels.audio());
+                    asset.start = asset.start.min(clip.source_in);
+                    asset.end = asset.end.max(clip.source_out);
+                }
+                None => assets.push(Asset {
+                    name,
+                    video: clip.av_channels.video(),
+                    audio: clip.av_channels.audio(),
+                    start: clip.source_in,
+                    end: clip.source_out,
+                }),
+            }
+        }
+        Assets(assets)
+    }
+
+    fn id(&self, name: &str) -> String {
+        let i = self
+            .0
+            .iter()
+            .position(|asset| asset.name == name)
+            .unwrap_or_default();
+        format!("r{}", i + 2)
+    }
+
+    fn write(&self, xml: &mut Xml, rate: Rate) {
+        for asset in self.0.iter() {
+            let duration = asset.end.frames() - asset.start.frames();
+            let audio_channels = asset.audio.to_string();
+            let mut attrs = vec![
+                ("id", self.id(asset.name)),
+                ("name", asset.name.into()),
+                ("start", time(asset.start.frames(), rate)),
+                ("duration", time(duration, rate)),
+                ("hasVideo", if asset.video { "1" } else { "0" }.into()),
+                ("hasAudio", if asset.audio > 0 { "1" } else { "0" }.into()),
+                ("format", FORMAT_ID.into()),
+            ];
+            if asset.audio > 0 {
+                attrs.push(("audioSources", "1".into()));
+                attrs.push(("audioChannels", audio_channels));
+            }
+            xml.open("asset", &borrow_attrs(&attrs))
+                .empty(
+                    "media-rep",
+                    &[("kind", "original-media"), ("src", &file_url(asset.name))],
+                )
+                .close();
+        }
+    }
+}
+
+// tape names are usually file names, so we point the asset at a relative file of that name
+fn file_url(name: &str) -> String {
+    name.bytes().fold(String::from("./"), |mut acc, byte| {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                acc.push(byte as char)
+            }
+            _ => acc.push_str(&format!("%{byte:02X}")),
+        };
+        acc
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::{test::session_events, ExportOpt};
+    use vtc::rates;
+
+    #[test]
+    fn fcpxml_project() {
+        let events = session_events(rates::F23_98);
+        let xml = render(&Session::new("my show", &events, &ExportOpt::default())).unwrap();
+
+        assert!(xml.contains("<fcpxml version=\"1.10\">"));
+        assert!(xml.contains("frameDuration=\"1001/24000s\""));
+        assert!(xml.contains("tcStart=\"86486400/24000s\""));
+        assert!(xml.contains("tcFormat=\"NDF\""));
+        assert_eq!(xml.matches("<asset id=").count(), 2);
+        assert!(xml.contains("src=\"./camera%20one%20long%20name.mov\""));
+        assert_eq!(xml.matches("<asset-clip ").count(), 3);
+        assert_eq!(xml.matches("name=\"camera one long name.mov\"").count(), 3);
+        assert!(xml.contains(
+            "<transition name=\"Wipe 012\" offset=\"86726640/24000s\" duration=\"10010/24000s\">"
+        ));
+        assert_eq!(xml.matches("<filter-video ").count(), 1);
+        assert!(xml.contains("srcEnable=\"video\""));
+
+        let events = session_events(rates::F29_97_DF);
+        let xml = render(&Session::new("my show", &events, &ExportOpt::default())).unwrap();
+        assert!(xml.contains("frameDuration=\"1001/30000s\""));
+        assert!(xml.contains("tcFormat=\"DF\""));
+    }
+}
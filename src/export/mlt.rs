@@ -0,0 +1,416 @@
+// MLT XML, as read by Kdenlive and Shotcut.
+// https://www.mltframework.org/docs/mltxml/
+//
+// MLT has no notion of source timecode, so source positions are counted from the source timecode
+// each tape's recorder was at on the first edit, or failing that from the start of the session, as
+// for iso recordings rolled when the session starts. The profile frame rate follows the
+// configured frame rate the timecodes were logged at.
+
+use anyhow::{Context, Error};
+
+use crate::{
+    edl_writer::{Clip, SourceTape},
+    export::{xml::Xml, Rate, Session, Transition},
+};
+
+const BLACK_ID: &str = "black";
+const PLAYLIST_ID: &str = "playlist0";
+
+pub fn render(session: &Session) -> Result<String, Error> {
+    let rate = session.rate().context("Session has no edits to export")?;
+    let producers = Producers::new(session);
+    let entries = entries(session, &producers);
+    let length: i64 = entries.iter().map(Entry::length).sum();
+
+    let mut xml = Xml::new();
+    xml.open(
+        "mlt",
+        &[
+            ("LC_NUMERIC", "C"),
+            ("version", "7.0.0"),
+            ("title", session.title),
+            ("producer", "main_bin"),
+        ],
+    );
+    write_profile(&mut xml, rate);
+    producers.write(&mut xml, length);
+
+    let (mut tractor_count, mut transition_count) = (0, 0);
+    for entry in entries.iter() {
+        if let Entry::Transition {
+            a,
+            b,
+            length,
+            wipe_number,
+        } = entry
+        {
+            let out = (length - 1).to_string();
+            tractor_count += 1;
+            xml.open(
+                "tractor",
+                &[
+                    ("id", &format!("tractor{}", tractor_count)),
+                    ("in", "0"),
+                    ("out", &out),
+                ],
+            )
+            .leaf_with("property", &[("name", "shotcut:transition")], "lumaMix");
+            for side in [a, b] {
+                xml.empty(
+                    "track",
+                    &[
+                        ("producer", &side.producer),
+                        ("in", &side.source_in.to_string()),
+                        ("out", &(side.source_in + length - 1).to_string()),
+                    ],
+                );
+            }
+            transition_count += 1;
+            xml.open(
+                "transition",
+                &[
+                    ("id", &format!("transition{}", transition_count)),
+                    ("out", &out),
+                ],
+            )
+            .leaf_with("property", &[("name", "a_track")], 0)
+            .leaf_with("property", &[("name", "b_track")], 1)
+            .leaf_with("property", &[("name", "factory")], "loader")
+            .leaf_with("property", &[("name", "mlt_service")], "luma");
+            if let Some(wipe_number) = wipe_number {
+                xml.leaf_with("property", &[("name", "resource")], "%luma01.pgm")
+                    .leaf_with("property", &[("name", "edl_gen:wipe_number")], wipe_number);
+            }
+            xml.close();
+            // wipes are video only, a dissolve also crossfades the audio
+            if wipe_number.is_none() {
+                transition_count += 1;
+                xml.open(
+                    "transition",
+                    &[
+                        ("id", &format!("transition{}", transition_count)),
+                        ("out", &out),
+                    ],
+                )
+                .leaf_with("property", &[("name", "a_track")], 0)
+                .leaf_with("property", &[("name", "b_track")], 1)
+                .leaf_with("property", &[("name", "mlt_service")], "mix")
+                .leaf_with("property", &[("name", "start")], -1)
+                .leaf_with("property", &[("name", "accepts_blanks")], 1)
+                .close();
+            }
+            xml.close();
+        }
+    }
+
+    xml.open("playlist", &[("id", PLAYLIST_ID)]);
+    tractor_count = 0;
+    for entry in entries.iter() {
+        match entry {
+            Entry::Clip(side) => xml.empty(
+                "entry",
+                &[
+                    ("producer", &side.producer),
+                    ("in", &side.source_in.to_string()),
+                    ("out", &(side.source_in + side.length - 1).to_string()),
+                ],
+            ),
+            Entry::Blank(length) => xml.empty("blank", &[("length", &length.to_string())]),
+            Entry::Transition { length, .. } => {
+                tractor_count += 1;
+                xml.empty(
+                    "entry",
+                    &[
+                        ("producer", &format!("tractor{}", tractor_count)),
+                        ("in", "0"),
+                        ("out", &(length - 1).to_string()),
+                    ],
+                )
+            }
+        };
+    }
+    xml.close()
+        .open(
+            "tractor",
+            &[
+                ("id", "tractor0"),
+                ("title", session.title),
+                ("in", "0"),
+                ("out", &(length - 1).to_string()),
+            ],
+        )
+        .leaf_with("property", &[("name", "shotcut")], 1)
+        .empty("track", &[("producer", PLAYLIST_ID)]);
+
+    Ok(xml.finish())
+}
+
+fn write_profile(xml: &mut Xml, rate: Rate) {
+    let (num, den) = rate.frame_duration();
+    xml.empty(
+        "profile",
+        &[
+            ("description", &format!("HD 1080p {:.2} fps", rate.fps())),
+            ("width", "1920"),
+            ("height", "1080"),
+            ("progressive", "1"),
+            ("sample_aspect_num", "1"),
+            ("sample_aspect_den", "1"),
+            ("display_aspect_num", "16"),
+            ("display_aspect_den", "9"),
+            ("frame_rate_num", &den.to_string()),
+            ("frame_rate_den", &num.to_string()),
+            ("colorspace", "709"),
+        ],
+    );
+}
+
+// a run of frames taken from one producer
+struct Span {
+    producer: String,
+    source_in: i64,
+    length: i64,
+}
+
+enum Entry {
+    Clip(Span),
+    Blank(i64),
+    // a two track tractor mixing out of `a` into `b`
+    Transition {
+        a: Span,
+        b: Span,
+        length: i64,
+        wipe_number: Option<u32>,
+    },
+}
+
+impl Entry {
+    fn length(&self) -> i64 {
+        match self {
+            Entry::Clip(span) => span.length,
+            Entry::Blank(length) => *length,
+            Entry::Transition { length, .. } => *length,
+        }
+    }
+}
+
+// CMX3600 transitions start on the edit point and run into the incoming clip, so the
+// outgoing side plays on past its out point for the length of the transition.
+fn entries(session: &Session, producers: &Producers) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    // the producer and source frame the previous entry ended on
+    let mut prev: Option<(String, i64)> = None;
+    for item in session.track() {
+        if item.gap > 0 {
+            entries.push(Entry::Blank(item.gap));
+            prev = None;
+        }
+        let producer = producers.id(item.clip);
+        let source_in = producers.position(item.clip, item.source_in);
+        let mut span = Span {
+            producer: producer.clone(),
+            source_in,
+            length: item.duration,
+        };
+
+        if let Some(transition) = Transition::from_event(item.event) {
+            let length = (transition.duration as i64).min(item.duration);
+            if length > 0 {
+                let (a_producer, a_in) = prev.clone().unwrap_or((BLACK_ID.into(), 0));
+                entries.push(Entry::Transition {
+                    a: Span {
+                        producer: a_producer,
+                        source_in: a_in,
+                        length,
+                    },
+                    b: Span {
+                        producer: producer.clone(),
+                        source_in,
+                        length,
+                    },
+                    length,
+                    wipe_number: transition.wipe_number,
+                });
+                span.source_in += length;
+                span.length -= length;
+            }
+        }
+
+        prev = Some((producer, span.source_in + span.length));
+        match (&item.clip.source_tape, span.length) {
+            (_, 0) => (),
+            (SourceTape::AX(_), _) => entries.push(Entry::Clip(span)),
+            (_, length) => {
+                entries.push(Entry::Blank(length));
+            }
+        }
+    }
+    entries
+}
+
+// one producer per source tape, along with the source frame its media starts at, plus a black
+// producer for transitions to and from black
+struct Producers<'a>(Vec<(&'a str, i64)>);
+
+impl<'a> Producers<'a> {
+    // a tape's media starts where its recorder was at the first edit, or failing that at the
+    // start of the session
+    fn new(session: &Session<'a>) -> Self {
+        let session_start = session.start().map(|tc| tc.frames()).unwrap_or_default();
+        let mut tapes: Vec<(&str, i64)> = Vec::new();
+        for clip in session.clips() {
+            if let SourceTape::AX(name) = &clip.source_tape {
+                if !tapes.iter().any(|(tape, _)| tape == name) {
+                    let start = session.opt.media_start(name).map(|tc| tc.frames());
+                    tapes.push((name, start.unwrap_or(session_start)));
+                }
+            }
+        }
+        Producers(tapes)
+    }
+
+    fn index(&self, clip: &Clip) -> Option<usize> {
+        match &clip.source_tape {
+            SourceTape::AX(name) => self.0.iter().position(|(tape, _)| tape == name),
+            _ => None,
+        }
+    }
+
+    fn id(&self, clip: &Clip) -> String {
+        match self.index(clip) {
+            Some(i) => format!("producer{}", i),
+            None => BLACK_ID.into(),
+        }
+    }
+
+    // the frame of the clip's producer a source frame lands on. Black starts wherever it's cut to.
+    fn position(&self, clip: &Clip, source_frame: i64) -> i64 {
+        match self.index(clip) {
+            Some(i) => source_frame - self.0[i].1,
+            None => 0,
+        }
+    }
+
+    fn write(&self, xml: &mut Xml, length: i64) {
+        xml.open("producer", &[("id", BLACK_ID)])
+            .leaf_with("property", &[("name", "length")], length)
+            .leaf_with("property", &[("name", "mlt_service")], "color")
+            .leaf_with("property", &[("name", "resource")], "#000000")
+            .close();
+        for (i, (name, _)) in self.0.iter().enumerate() {
+            xml.open("producer", &[("id", &format!("producer{}", i))])
+                .leaf_with("property", &[("name", "resource")], name)
+                .leaf_with(
+                    "property",
+                    &[("name", "mlt_service")],
+                    "avformat-novalidate",
+                )
+                .leaf_with("property", &[("name", "shotcut:caption")], name)
+                .close();
+        }
+        xml.open("playlist", &[("id", "main_bin")]).leaf_with(
+            "property",
+            &[("name", "xml_retain")],
+            1,
+        );
+        for i in 0..self.0.len() {
+            xml.empty("entry", &[("producer", &format!("producer{}", i))]);
+        }
+        xml.close();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        edl_writer::{
+            edit_queue::{Edit, EditQueue},
+            AVChannels, Event, Handles, OrderedEditInOutPair,
+        },
+        export::{test::session_events, ExportOpt},
+    };
+    use vtc::{rates, Timecode};
+
+    #[test]
+    fn mlt_project() {
+        let events = session_events(rates::F29_97_NDF);
+        let xml = render(&Session::new("my show", &events, &ExportOpt::default())).unwrap();
+
+        assert!(xml.contains("frame_rate_num=\"30000\" frame_rate_den=\"1001\""));
+        assert!(xml.contains("<property name=\"resource\">camera one long name.mov</property>"));
+        assert!(xml.contains("<property name=\"resource\">cam2</property>"));
+        assert_eq!(xml.matches("<producer id=").count(), 3);
+
+        // cut, wipe into cam2, dissolve back
+        assert!(xml.contains(
+            "<entry producer=\"producer0\" in=\"0\" out=\"299\"/>\n    \
+             <entry producer=\"tractor1\" in=\"0\" out=\"9\"/>\n    \
+             <entry producer=\"producer1\" in=\"310\" out=\"599\"/>\n    \
+             <entry producer=\"tractor2\" in=\"0\" out=\"11\"/>\n    \
+             <entry producer=\"producer0\" in=\"612\" out=\"899\"/>"
+        ));
+        assert!(xml.contains(
+            "<track producer=\"producer0\" in=\"300\" out=\"309\"/>\n    \
+             <track producer=\"producer1\" in=\"300\" out=\"309\"/>"
+        ));
+        assert!(xml.contains("<property name=\"edl_gen:wipe_number\">12</property>"));
+        assert_eq!(
+            xml.matches("<property name=\"mlt_service\">luma</property>")
+                .count(),
+            2
+        );
+        assert_eq!(
+            xml.matches("<property name=\"mlt_service\">mix</property>")
+                .count(),
+            1
+        );
+        assert!(xml.contains("<tractor id=\"tractor0\" title=\"my show\" in=\"0\" out=\"899\">"));
+    }
+
+    #[test]
+    fn source_positions_per_tape() {
+        // time-of-day LTC on a continuous record timeline from 01:00:00:00, with a head handle
+        let tc = |tc: &str| Timecode::with_frames(tc, rates::F24).unwrap();
+        let mut queue = EditQueue::with_record_start(Some(tc("01:00:00:00")));
+        let mut events: Vec<Event> = Vec::new();
+        for (tape, ltc) in [
+            (Some("a"), "14:00:00:00"),
+            (Some("b"), "14:00:10:00"),
+            (Some("a"), "14:00:20:00"),
+            (None, "14:00:30:00"),
+        ] {
+            queue
+                .push(Edit {
+                    source_tape: tape.map(String::from),
+                    av_channels: AVChannels::default(),
+                    ..Edit::black(tc(ltc))
+                })
+                .unwrap();
+            let out_ = queue.front().unwrap().clone();
+            while let Some(in_) = queue.pop_closed() {
+                let event = OrderedEditInOutPair::new(&in_, &out_)
+                    .with_handles(Handles { head: 48, tail: 0 })
+                    .try_into()
+                    .unwrap();
+                events.push(event);
+            }
+        }
+        // both recorders rolled on the LTC of the first edit
+        let opt = ExportOpt {
+            media_starts: vec![
+                ("a".into(), tc("14:00:00:00")),
+                ("b".into(), tc("14:00:00:00")),
+            ],
+            ..ExportOpt::default()
+        };
+        let xml = render(&Session::new("my show", &events, &opt)).unwrap();
+
+        // handles are left to the EDL, each entry runs for its record range
+        assert!(xml.contains(
+            "<entry producer=\"producer0\" in=\"0\" out=\"239\"/>\n    \
+             <entry producer=\"producer1\" in=\"240\" out=\"479\"/>\n    \
+             <entry producer=\"producer0\" in=\"480\" out=\"719\"/>"
+        ));
+    }
+}
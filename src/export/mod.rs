@@ -0,0 +1,395 @@
+// Timeline exports written alongside the CMX3600 EDL once a session ends.
+// These are built from the logged `Event`s rather than the EDL text, so they keep the data
+// CMX3600 has no room for (full tape names, wipe numbers, more than four audio channels).
+
+pub mod adl;
+pub mod ale;
+pub mod chapters;
+pub mod fcpxml;
+pub mod mlt;
+pub mod otio;
+pub mod xmeml;
+mod xml;
+
+use anyhow::{anyhow, Context, Error};
+use vtc::{Framerate, Timecode};
+
+use std::{
+    fmt,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    edl_writer::{reel::parse_tape_pairs, Clip, Event},
+    state::Opt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Xmeml,
+    Fcpxml,
+    Otio,
+    Mlt,
+    Ale,
+    Adl,
+    Srt,
+    WebVtt,
+    YoutubeChapters,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 9] = [
+        ExportFormat::Xmeml,
+        ExportFormat::Fcpxml,
+        ExportFormat::Otio,
+        ExportFormat::Mlt,
+        ExportFormat::Ale,
+        ExportFormat::Adl,
+        ExportFormat::Srt,
+        ExportFormat::WebVtt,
+        ExportFormat::YoutubeChapters,
+    ];
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Xmeml => "xml",
+            ExportFormat::Fcpxml => "fcpxml",
+            ExportFormat::Otio => "otio",
+            ExportFormat::Mlt => "mlt",
+            ExportFormat::Ale => "ale",
+            ExportFormat::Adl => "adl",
+            ExportFormat::Srt => "srt",
+            ExportFormat::WebVtt => "vtt",
+            ExportFormat::YoutubeChapters => "chapters.txt",
+        }
+    }
+
+    fn render(&self, session: &Session) -> Result<String, Error> {
+        match self {
+            ExportFormat::Xmeml => xmeml::render(session),
+            ExportFormat::Fcpxml => fcpxml::render(session),
+            ExportFormat::Otio => otio::render(session),
+            ExportFormat::Mlt => mlt::render(session),
+            ExportFormat::Ale => ale::render(session),
+            ExportFormat::Adl => adl::render(session),
+            ExportFormat::Srt => chapters::render_srt(session),
+            ExportFormat::WebVtt => chapters::render_vtt(session),
+            ExportFormat::YoutubeChapters => chapters::render_youtube(session),
+        }
+    }
+
+    pub fn write(&self, session: &Session, edl_path: &Path) -> Result<(), Error> {
+        if session.events.is_empty() {
+            log::warn!("No edits were logged, skipping {} export.", self);
+            return Ok(());
+        }
+        let path = edl_path.with_extension(self.extension());
+        let mut file = BufWriter::new(
+            File::create_new(&path).with_context(|| format!("Could not create {} file", self))?,
+        );
+        file.write_all(self.render(session)?.as_bytes())?;
+        file.flush()?;
+        log::info!("{} written to {}", self, path.display());
+        Ok(())
+    }
+}
+
+impl From<ExportFormat> for &str {
+    fn from(value: ExportFormat) -> Self {
+        match value {
+            ExportFormat::Xmeml => "FCP7 XML",
+            ExportFormat::Fcpxml => "FCPXML",
+            ExportFormat::Otio => "OTIO",
+            ExportFormat::Mlt => "MLT",
+            ExportFormat::Ale => "ALE",
+            ExportFormat::Adl => "AES31 ADL",
+            ExportFormat::Srt => "SRT",
+            ExportFormat::WebVtt => "WebVTT",
+            ExportFormat::YoutubeChapters => "YouTube Chapters",
+        }
+    }
+}
+
+impl TryFrom<&str> for ExportFormat {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        ExportFormat::ALL
+            .into_iter()
+            .find(|format| <&str>::from(*format) == value)
+            .ok_or_else(|| anyhow!("Invalid export format '{}'", value))
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str((*self).into())
+    }
+}
+
+// the project settings exports draw on, taken from `Opt` when the session starts
+#[derive(Debug, Clone)]
+pub struct ExportOpt {
+    pub sample_rate: usize,
+    pub source_labels: Vec<(String, String)>,
+    pub chapter_min_secs: u32,
+    // tape name and the source timecode its media starts at, set as the session ends
+    pub media_starts: Vec<(String, Timecode)>,
+}
+
+impl ExportOpt {
+    // the label for a source tape, falling back to the tape name
+    pub fn label<'a>(&'a self, tape: &'a str) -> &'a str {
+        self.source_labels
+            .iter()
+            .find(|(name, _)| name == tape)
+            .map(|(_, label)| label.as_str())
+            .unwrap_or(tape)
+    }
+
+    pub fn media_start(&self, tape: &str) -> Option<Timecode> {
+        self.media_starts
+            .iter()
+            .find(|(name, _)| name == tape)
+            .map(|(_, start)| *start)
+    }
+}
+
+impl From<&Opt> for ExportOpt {
+    fn from(opt: &Opt) -> Self {
+        ExportOpt {
+            sample_rate: opt.sample_rate,
+            source_labels: parse_tape_pairs(&opt.source_labels),
+            chapter_min_secs: opt.chapter_min_secs,
+            media_starts: Vec::new(),
+        }
+    }
+}
+
+impl Default for ExportOpt {
+    fn default() -> Self {
+        ExportOpt {
+            sample_rate: 48_000,
+            source_labels: Vec::new(),
+            chapter_min_secs: 0,
+            media_starts: Vec::new(),
+        }
+    }
+}
+
+pub struct Session<'a> {
+    pub title: &'a str,
+    pub events: &'a [Event],
+    pub opt: &'a ExportOpt,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(title: &'a str, events: &'a [Event], opt: &'a ExportOpt) -> Self {
+        Session { title, events, opt }
+    }
+
+    // the clip each event records onto the program timeline
+    pub fn clips(&self) -> impl Iterator<Item = &'a Clip> {
+        self.events.iter().map(<&Clip>::from)
+    }
+
+    pub fn start(&self) -> Option<Timecode> {
+        self.clips().map(|clip| clip.record_in).min()
+    }
+
+    pub fn end(&self) -> Option<Timecode> {
+        self.clips().map(|clip| clip.record_out).max()
+    }
+
+    pub fn rate(&self) -> Option<Rate> {
+        self.start().map(|tc| Rate(tc.rate()))
+    }
+
+    // frame offset of a record timecode from the start of the session
+    pub fn offset(&self, tc: Timecode) -> i64 {
+        self.start()
+            .map(|start| tc.frames() - start.frames())
+            .unwrap_or_default()
+    }
+
+    // lays the session's clips end to end for formats where items on a track can't overlap.
+    // A clip running past the next edit point (ie. a transition longer than the edit) pushes
+    // the start of the next clip back, and any hole between clips is noted as a gap.
+    pub fn track(&self) -> Vec<TrackItem<'a>> {
+        let Some(start) = self.start() else {
+            return Vec::new();
+        };
+        let mut cursor = start.frames();
+        self.events
+            .iter()
+            .filter_map(|event| {
+                let clip: &Clip = event.into();
+                let record_in = clip.record_in.frames().max(cursor);
+                let record_out = clip.record_out.frames();
+                if record_out <= record_in {
+                    return None;
+                }
+                let item = TrackItem {
+                    event,
+                    clip,
+                    gap: record_in - cursor,
+                    record_in,
+                    source_in: clip.source_in.frames() + record_in - clip.record_in.frames(),
+                    duration: record_out - record_in,
+                };
+                cursor = record_out;
+                Some(item)
+            })
+            .collect()
+    }
+}
+
+pub struct TrackItem<'a> {
+    pub event: &'a Event,
+    pub clip: &'a Clip,
+    // frames left empty between the previous item and this one
+    pub gap: i64,
+    pub record_in: i64,
+    pub source_in: i64,
+    pub duration: i64,
+}
+
+pub struct Transition<'a> {
+    pub from: &'a Clip,
+    pub to: &'a Clip,
+    pub duration: u32,
+    pub wipe_number: Option<u32>,
+}
+
+impl<'a> Transition<'a> {
+    pub fn from_event(event: &'a Event) -> Option<Self> {
+        match event {
+            // keys are left to the EDL, exports carry the background only
+            Event::Cut(_) | Event::Key(_) => None,
+            Event::Dissolve(dissolve) => Some(Transition {
+                from: &dissolve.from,
+                to: &dissolve.to,
+                duration: dissolve.edit_duration_frames,
+                wipe_number: None,
+            }),
+            Event::Wipe(wipe) => Some(Transition {
+                from: &wipe.from,
+                to: &wipe.to,
+                duration: wipe.edit_duration_frames,
+                wipe_number: Some(wipe.wipe_number),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Rate(Framerate);
+
+impl Rate {
+    pub fn timebase(&self) -> i64 {
+        self.0.timebase().to_integer()
+    }
+
+    pub fn is_ntsc(&self) -> bool {
+        matches!(
+            self.0.ntsc(),
+            vtc::Ntsc::NonDropFrame | vtc::Ntsc::DropFrame
+        )
+    }
+
+    pub fn is_drop_frame(&self) -> bool {
+        matches!(self.0.ntsc(), vtc::Ntsc::DropFrame)
+    }
+
+    // seconds per frame as a (numerator, denominator) pair
+    pub fn frame_duration(&self) -> (i64, i64) {
+        match self.is_ntsc() {
+            true => (1001, self.timebase() * 1000),
+            false => (1, self.timebase()),
+        }
+    }
+
+    pub fn fps(&self) -> f64 {
+        let (num, den) = self.frame_duration();
+        den as f64 / num as f64
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use vtc::{Framerate, Timecode};
+
+    use crate::edl_writer::{AVChannels, Clip, Dissolve, Event, Handles, Wipe};
+
+    pub fn clip(
+        edit_number: usize,
+        tape: Option<&str>,
+        av_channels: AVChannels,
+        (tc_in, tc_out): (&str, &str),
+        rate: Framerate,
+    ) -> Clip {
+        let tc_in = Timecode::with_frames(tc_in, rate).unwrap();
+        let tc_out = Timecode::with_frames(tc_out, rate).unwrap();
+        Clip {
+            edit_number,
+            source_tape: tape.into(),
+            av_channels,
+            source_in: tc_in,
+            source_out: tc_out,
+            record_in: tc_in,
+            record_out: tc_out,
+            speed: None,
+            handles: Handles::default(),
+        }
+    }
+
+    // a cut, a wipe to a video only source, then a dissolve back with four audio channels
+    pub fn session_events(rate: Framerate) -> Vec<Event> {
+        let long_name = Some("camera one long name.mov");
+        vec![
+            Event::Cut(clip(
+                1,
+                long_name,
+                AVChannels::new(true, 2),
+                ("01:00:00:00", "01:00:10:00"),
+                rate,
+            )),
+            Event::Wipe(Wipe {
+                from: clip(
+                    2,
+                    long_name,
+                    AVChannels::new(true, 2),
+                    ("01:00:10:00", "01:00:10:00"),
+                    rate,
+                ),
+                to: clip(
+                    2,
+                    Some("cam2"),
+                    AVChannels::video_only(),
+                    ("01:00:10:00", "01:00:20:00"),
+                    rate,
+                ),
+                wipe_number: 12,
+                edit_duration_frames: 10,
+            }),
+            Event::Dissolve(Dissolve {
+                from: clip(
+                    3,
+                    Some("cam2"),
+                    AVChannels::video_only(),
+                    ("01:00:20:00", "01:00:20:00"),
+                    rate,
+                ),
+                to: clip(
+                    3,
+                    long_name,
+                    AVChannels::new(true, 4),
+                    ("01:00:20:00", "01:00:30:00"),
+                    rate,
+                ),
+                edit_duration_frames: 12,
+            }),
+        ]
+    }
+}
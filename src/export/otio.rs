@@ -0,0 +1,240 @@
+// OpenTimelineIO JSON, as read by `otiotool` and the OTIO adapters.
+// https://opentimelineio.readthedocs.io/en/latest/tutorials/otio-serialized-schema.html
+
+use anyhow::{Context, Error};
+use serde_json::{json, Value};
+
+use crate::{
+    edl_writer::{Clip, SourceTape},
+    export::{Rate, Session, Transition},
+};
+
+pub fn render(session: &Session) -> Result<String, Error> {
+    let start = session.start().context("Session has no edits to export")?;
+    let rate = session.rate().context("Session has no edits to export")?;
+    let max_audio = session
+        .clips()
+        .map(|clip| clip.av_channels.audio())
+        .max()
+        .unwrap_or(0);
+
+    let mut tracks = vec![track(session, rate, Media::Video)];
+    tracks.extend((1..=max_audio).map(|channel| track(session, rate, Media::Audio(channel))));
+
+    let timeline = json!({
+        "OTIO_SCHEMA": "Timeline.1",
+        "metadata": {},
+        "name": session.title,
+        "global_start_time": rational_time(start.frames(), rate),
+        "tracks": {
+            "OTIO_SCHEMA": "Stack.1",
+            "metadata": {},
+            "name": "tracks",
+            "source_range": null,
+            "effects": [],
+            "markers": [],
+            "children": tracks,
+        },
+    });
+    Ok(serde_json::to_string_pretty(&timeline)?)
+}
+
+#[derive(Clone, Copy)]
+enum Media {
+    Video,
+    Audio(u8),
+}
+
+impl Media {
+    fn carries(&self, clip: &Clip) -> bool {
+        match (self, &clip.source_tape) {
+            (_, SourceTape::BL | SourceTape::Bars | SourceTape::Aux) => false,
+            (Media::Video, _) => clip.av_channels.video(),
+            (Media::Audio(channel), _) => clip.av_channels.audio() >= *channel,
+        }
+    }
+}
+
+fn track(session: &Session, rate: Rate, media: Media) -> Value {
+    let mut children: Vec<Value> = Vec::new();
+    let mut gap = 0;
+    let mut prev_clip = false;
+    for item in session.track() {
+        gap += item.gap;
+        let is_clip = media.carries(item.clip);
+        // audio tracks only take the dissolves, wipes are a cut
+        let transition = Transition::from_event(item.event)
+            .filter(|transition| matches!(media, Media::Video) || transition.wipe_number.is_none());
+        match (is_clip, transition) {
+            (true, transition) => {
+                if gap > 0 {
+                    children.push(gap_item(gap, rate));
+                    gap = 0;
+                }
+                if let Some(transition) = transition {
+                    children.push(transition_item(&transition, rate));
+                }
+                children.push(clip_item(item.clip, item.source_in, item.duration, rate));
+            }
+            // a transition out to black needs an item on either side of it
+            (false, Some(transition)) if prev_clip && gap == 0 => {
+                children.push(transition_item(&transition, rate));
+                children.push(gap_item(item.duration, rate));
+            }
+            (false, _) => gap += item.duration,
+        }
+        prev_clip = is_clip;
+    }
+
+    let (name, kind) = match media {
+        Media::Video => ("V1".to_string(), "Video"),
+        Media::Audio(channel) => (format!("A{}", channel), "Audio"),
+    };
+    json!({
+        "OTIO_SCHEMA": "Track.1",
+        "metadata": {},
+        "name": name,
+        "source_range": null,
+        "effects": [],
+        "markers": [],
+        "kind": kind,
+        "children": children,
+    })
+}
+
+fn clip_item(clip: &Clip, source_in: i64, duration: i64, rate: Rate) -> Value {
+    let name = <&str>::from(&clip.source_tape);
+    json!({
+        "OTIO_SCHEMA": "Clip.2",
+        "metadata": {
+            "edl_gen": {
+                "edit_number": clip.edit_number,
+                "source_tape": name,
+                "video": clip.av_channels.video(),
+                "audio_channels": clip.av_channels.audio(),
+            },
+        },
+        "name": name,
+        "source_range": time_range(source_in, duration, rate),
+        "effects": [],
+        "markers": [],
+        "enabled": true,
+        "media_references": {
+            "DEFAULT_MEDIA": {
+                "OTIO_SCHEMA": "ExternalReference.1",
+                "metadata": {},
+                "name": name,
+                "available_range": null,
+                "available_image_bounds": null,
+                "target_url": name,
+            },
+        },
+        "active_media_reference_key": "DEFAULT_MEDIA",
+    })
+}
+
+// CMX3600 transitions start on the edit point and run into the incoming clip, so the whole
+// duration is taken from the clip after the transition.
+fn transition_item(transition: &Transition, rate: Rate) -> Value {
+    let (name, transition_type, metadata) = match transition.wipe_number {
+        Some(wipe_number) => (
+            format!("Wipe {:03}", wipe_number),
+            "Custom_Transition",
+            json!({ "edl_gen": { "wipe_number": wipe_number } }),
+        ),
+        None => ("Dissolve".to_string(), "SMPTE_Dissolve", json!({})),
+    };
+    json!({
+        "OTIO_SCHEMA": "Transition.1",
+        "metadata": metadata,
+        "name": name,
+        "transition_type": transition_type,
+        "in_offset": rational_time(0, rate),
+        "out_offset": rational_time(transition.duration as i64, rate),
+    })
+}
+
+fn gap_item(duration: i64, rate: Rate) -> Value {
+    json!({
+        "OTIO_SCHEMA": "Gap.1",
+        "metadata": {},
+        "name": "",
+        "source_range": time_range(0, duration, rate),
+        "effects": [],
+        "markers": [],
+    })
+}
+
+fn time_range(start: i64, duration: i64, rate: Rate) -> Value {
+    json!({
+        "OTIO_SCHEMA": "TimeRange.1",
+        "start_time": rational_time(start, rate),
+        "duration": rational_time(duration, rate),
+    })
+}
+
+fn rational_time(frames: i64, rate: Rate) -> Value {
+    json!({
+        "OTIO_SCHEMA": "RationalTime.1",
+        "rate": rate.fps(),
+        "value": frames as f64,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::{test::session_events, ExportOpt};
+    use vtc::rates;
+
+    #[test]
+    fn otio_timeline() {
+        let events = session_events(rates::F23_98);
+        let otio: Value = serde_json::from_str(
+            &render(&Session::new("my show", &events, &ExportOpt::default())).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(otio["OTIO_SCHEMA"], "Timeline.1");
+        assert_eq!(otio["name"], "my show");
+        assert_eq!(otio["global_start_time"]["value"], 86400.0);
+        assert!((otio["global_start_time"]["rate"].as_f64().unwrap() - 23.976).abs() < 0.001);
+
+        let tracks = otio["tracks"]["children"].as_array().unwrap();
+        let names: Vec<&str> = tracks.iter().map(|t| t["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["V1", "A1", "A2", "A3", "A4"]);
+
+        let schemas = |track: &Value| -> Vec<String> {
+            track["children"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item["OTIO_SCHEMA"].as_str().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(
+            schemas(&tracks[0]),
+            ["Clip.2", "Transition.1", "Clip.2", "Transition.1", "Clip.2"]
+        );
+        assert_eq!(
+            schemas(&tracks[1]),
+            ["Clip.2", "Gap.1", "Transition.1", "Clip.2"]
+        );
+        assert_eq!(schemas(&tracks[3]), ["Gap.1", "Transition.1", "Clip.2"]);
+
+        let video = &tracks[0]["children"];
+        assert_eq!(video[0]["name"], "camera one long name.mov");
+        assert_eq!(
+            video[0]["media_references"]["DEFAULT_MEDIA"]["target_url"],
+            "camera one long name.mov"
+        );
+        assert_eq!(video[0]["source_range"]["start_time"]["value"], 86400.0);
+        assert_eq!(video[0]["source_range"]["duration"]["value"], 240.0);
+        assert_eq!(video[1]["transition_type"], "Custom_Transition");
+        assert_eq!(video[1]["metadata"]["edl_gen"]["wipe_number"], 12);
+        assert_eq!(video[1]["out_offset"]["value"], 10.0);
+        assert_eq!(video[2]["name"], "cam2");
+        assert_eq!(video[3]["transition_type"], "SMPTE_Dissolve");
+        assert_eq!(video[4]["metadata"]["edl_gen"]["audio_channels"], 4);
+    }
+}
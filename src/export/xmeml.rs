@@ -0,0 +1,314 @@
+// Final Cut Pro 7 XML (xmeml v5), as read by Premiere Pro and FCP7 era tools.
+// https://developer.apple.com/library/archive/documentation/AppleApplications/Reference/FinalCutPro_XML/
+
+use anyhow::{Context, Error};
+use vtc::Timecode;
+
+use crate::{
+    edl_writer::{Clip, Event, SourceTape},
+    export::{xml::Xml, Rate, Session, Transition},
+};
+
+pub fn render(session: &Session) -> Result<String, Error> {
+    let start = session.start().context("Session has no edits to export")?;
+    let end = session.end().context("Session has no edits to export")?;
+    let rate = session.rate().context("Session has no edits to export")?;
+    let mut seq = Sequence {
+        xml: Xml::new(),
+        files: Files::new(session),
+        item_count: 0,
+        session,
+        rate,
+    };
+
+    seq.xml
+        .doctype("xmeml")
+        .open("xmeml", &[("version", "5")])
+        .open("sequence", &[("id", "sequence-1")])
+        .leaf("name", session.title)
+        .leaf("duration", session.offset(end));
+    seq.rate();
+    seq.timecode(start);
+    seq.xml.open("media", &[]).open("video", &[]);
+    seq.xml
+        .open("format", &[])
+        .open("samplecharacteristics", &[]);
+    seq.rate();
+    seq.xml.close().close();
+    seq.video_track();
+    seq.xml.close().open("audio", &[]);
+    (1..=seq.files.max_audio()).for_each(|channel| seq.audio_track(channel));
+    seq.xml.close();
+
+    Ok(seq.xml.finish())
+}
+
+struct Sequence<'a> {
+    xml: Xml,
+    files: Files<'a>,
+    item_count: usize,
+    session: &'a Session<'a>,
+    rate: Rate,
+}
+
+impl<'a> Sequence<'a> {
+    fn video_track(&mut self) {
+        self.xml.open("track", &[]);
+        for event in self.session.events {
+            self.transition_item(event, Media::Video);
+            let clip: &Clip = event.into();
+            if clip.av_channels.video() {
+                self.clip_item(clip, None);
+            }
+        }
+        self.xml.close();
+    }
+
+    fn audio_track(&mut self, channel: u8) {
+        self.xml.open("track", &[]);
+        for event in self.session.events {
+            let clip: &Clip = event.into();
+            if clip.av_channels.audio() >= channel {
+                if let Event::Dissolve(_) = event {
+                    self.transition_item(event, Media::Audio);
+                }
+                self.clip_item(clip, Some(channel));
+            }
+        }
+        self.xml.close();
+    }
+
+    fn clip_item(&mut self, clip: &Clip, audio_channel: Option<u8>) {
+        let SourceTape::AX(name) = &clip.source_tape else {
+            return;
+        };
+        self.item_count += 1;
+        self.xml
+            .open(
+                "clipitem",
+                &[("id", &format!("clipitem-{}", self.item_count))],
+            )
+            .leaf("name", name)
+            .leaf("enabled", "TRUE")
+            .leaf(
+                "duration",
+                clip.source_out.frames() - clip.source_in.frames(),
+            );
+        self.rate();
+        self.xml
+            .leaf("start", self.session.offset(clip.record_in))
+            .leaf("end", self.session.offset(clip.record_out))
+            .leaf("in", clip.source_in.frames())
+            .leaf("out", clip.source_out.frames());
+        self.files.write(&mut self.xml, name, self.rate);
+        if let Some(channel) = audio_channel {
+            self.xml
+                .open("sourcetrack", &[])
+                .leaf("mediatype", "audio")
+                .leaf("trackindex", channel)
+                .close();
+        }
+        self.xml
+            .open("comments", &[])
+            .leaf("mastercomment1", format!("Edit {:03}", clip.edit_number))
+            .close()
+            .close();
+    }
+
+    fn transition_item(&mut self, event: &Event, media: Media) {
+        let Some(Transition {
+            from,
+            to,
+            duration,
+            wipe_number,
+        }) = Transition::from_event(event)
+        else {
+            return;
+        };
+        // CMX3600 transitions start on the edit point and run into the incoming clip
+        let alignment = match (&from.source_tape, &to.source_tape) {
+            (SourceTape::BL, _) => "start-black",
+            (_, SourceTape::BL) => "end-black",
+            _ => "start",
+        };
+        let start = self.session.offset(to.record_in);
+
+        self.xml.open("transitionitem", &[]);
+        self.rate();
+        self.xml
+            .leaf("start", start)
+            .leaf("end", start + duration as i64)
+            .leaf("alignment", alignment)
+            .open("effect", &[]);
+        match (media, wipe_number) {
+            (Media::Audio, _) => self
+                .xml
+                .leaf("name", "Cross Fade (+3dB)")
+                .leaf("effectid", "KGAudioTransCrossFade3dB")
+                .leaf("effectcategory", "Crossfade")
+                .leaf("effecttype", "transition")
+                .leaf("mediatype", "audio"),
+            (Media::Video, None) => self
+                .xml
+                .leaf("name", "Cross Dissolve")
+                .leaf("effectid", "Cross Dissolve")
+                .leaf("effectcategory", "Dissolve")
+                .leaf("effecttype", "transition")
+                .leaf("mediatype", "video"),
+            (Media::Video, Some(wipe_number)) => self
+                .xml
+                .leaf("name", "Wipe")
+                .leaf("effectid", "Wipe")
+                .leaf("effectcategory", "Wipe")
+                .leaf("effecttype", "transition")
+                .leaf("mediatype", "video")
+                .open("parameter", &[])
+                .leaf("parameterid", "wipecode")
+                .leaf("name", "Wipe Code")
+                .leaf("value", wipe_number)
+                .close(),
+        };
+        self.xml.close().close();
+    }
+
+    fn rate(&mut self) {
+        write_rate(&mut self.xml, self.rate);
+    }
+
+    fn timecode(&mut self, tc: Timecode) {
+        self.xml.open("timecode", &[]);
+        self.rate();
+        self.xml
+            .leaf("string", tc.timecode())
+            .leaf("frame", tc.frames())
+            .leaf(
+                "displayformat",
+                if self.rate.is_drop_frame() {
+                    "DF"
+                } else {
+                    "NDF"
+                },
+            )
+            .close();
+    }
+}
+
+fn write_rate(xml: &mut Xml, rate: Rate) {
+    xml.open("rate", &[])
+        .leaf("timebase", rate.timebase())
+        .leaf("ntsc", if rate.is_ntsc() { "TRUE" } else { "FALSE" })
+        .close();
+}
+
+#[derive(Clone, Copy)]
+enum Media {
+    Video,
+    Audio,
+}
+
+// one file element per source tape. The first clipitem referencing a tape carries the full
+// definition, every later one refers back to it by id.
+struct Files<'a> {
+    tapes: Vec<SourceFile<'a>>,
+}
+
+struct SourceFile<'a> {
+    name: &'a str,
+    video: bool,
+    audio: u8,
+    defined: bool,
+}
+
+impl<'a> Files<'a> {
+    fn new(session: &Session<'a>) -> Self {
+        let mut tapes: Vec<SourceFile> = Vec::new();
+        for clip in session.clips() {
+            let SourceTape::AX(name) = &clip.source_tape else {
+                continue;
+            };
+            let (video, audio) = (clip.av_channels.video(), clip.av_channels.audio());
+            match tapes.iter_mut().find(|tape| tape.name == name) {
+                Some(tape) => {
+                    tape.video |= video;
+                    tape.audio = tape.audio.max(audio);
+                }
+                None => tapes.push(SourceFile {
+                    name,
+                    video,
+                    audio,
+                    defined: false,
+                }),
+            }
+        }
+        Files { tapes }
+    }
+
+    fn max_audio(&self) -> u8 {
+        self.tapes.iter().map(|tape| tape.audio).max().unwrap_or(0)
+    }
+
+    fn write(&mut self, xml: &mut Xml, name: &str, rate: Rate) {
+        let Some((i, tape)) = self
+            .tapes
+            .iter_mut()
+            .enumerate()
+            .find(|(_, tape)| tape.name == name)
+        else {
+            return;
+        };
+        let id = format!("file-{}", i + 1);
+        if tape.defined {
+            xml.empty("file", &[("id", &id)]);
+            return;
+        }
+        tape.defined = true;
+        xml.open("file", &[("id", &id)]).leaf("name", tape.name);
+        write_rate(xml, rate);
+        xml.open("media", &[]);
+        if tape.video {
+            xml.empty("video", &[]);
+        }
+        if tape.audio > 0 {
+            xml.open("audio", &[])
+                .leaf("channelcount", tape.audio)
+                .close();
+        }
+        xml.close().close();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::{test::session_events, ExportOpt};
+    use vtc::rates;
+
+    #[test]
+    fn xmeml_sequence() {
+        let events = session_events(rates::F24);
+        let xml = render(&Session::new("my <show>", &events, &ExportOpt::default())).unwrap();
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE xmeml>"));
+        assert!(xml.contains("<name>my &lt;show&gt;</name>"));
+        assert!(xml.contains("<duration>720</duration>"));
+        assert!(xml.contains("<string>01:00:00:00</string>"));
+        assert!(xml.contains("<displayformat>NDF</displayformat>"));
+        assert_eq!(
+            xml.matches("<name>camera one long name.mov</name>").count(),
+            9
+        );
+        assert_eq!(xml.matches("<file id=\"file-1\">").count(), 1);
+        assert_eq!(xml.matches("<file id=\"file-1\"/>").count(), 7);
+        assert_eq!(xml.matches("<clipitem ").count(), 9);
+        assert!(xml.contains("<value>12</value>"));
+        assert!(xml.contains("<alignment>start</alignment>"));
+        assert!(xml.contains("<start>240</start>\n            <end>250</end>"));
+        assert_eq!(
+            xml.matches("<effectid>KGAudioTransCrossFade3dB</effectid>")
+                .count(),
+            4
+        );
+        assert_eq!(xml.matches("<trackindex>4</trackindex>").count(), 1);
+        assert_eq!(xml.matches("</track>").count(), 5);
+    }
+}
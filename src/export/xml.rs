@@ -0,0 +1,94 @@
+use std::fmt::Display;
+
+// Minimal indented XML writer for the XML based exports. Elements must be closed in the order
+// they were opened.
+pub(crate) struct Xml {
+    buf: String,
+    open: Vec<&'static str>,
+}
+
+impl Xml {
+    pub fn new() -> Self {
+        Xml {
+            buf: "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".into(),
+            open: Vec::new(),
+        }
+    }
+
+    pub fn doctype(&mut self, name: &str) -> &mut Self {
+        self.buf.push_str(&format!("<!DOCTYPE {name}>\n"));
+        self
+    }
+
+    pub fn open(&mut self, tag: &'static str, attrs: &[(&str, &str)]) -> &mut Self {
+        self.indent();
+        self.buf
+            .push_str(&format!("<{tag}{}>\n", Xml::attrs(attrs)));
+        self.open.push(tag);
+        self
+    }
+
+    pub fn close(&mut self) -> &mut Self {
+        if let Some(tag) = self.open.pop() {
+            self.indent();
+            self.buf.push_str(&format!("</{tag}>\n"));
+        }
+        self
+    }
+
+    pub fn leaf<T: Display>(&mut self, tag: &str, text: T) -> &mut Self {
+        self.indent();
+        self.buf
+            .push_str(&format!("<{tag}>{}</{tag}>\n", escape(&text.to_string())));
+        self
+    }
+
+    pub fn leaf_with<T: Display>(
+        &mut self,
+        tag: &str,
+        attrs: &[(&str, &str)],
+        text: T,
+    ) -> &mut Self {
+        self.indent();
+        self.buf.push_str(&format!(
+            "<{tag}{}>{}</{tag}>\n",
+            Xml::attrs(attrs),
+            escape(&text.to_string())
+        ));
+        self
+    }
+
+    pub fn empty(&mut self, tag: &str, attrs: &[(&str, &str)]) -> &mut Self {
+        self.indent();
+        self.buf
+            .push_str(&format!("<{tag}{}/>\n", Xml::attrs(attrs)));
+        self
+    }
+
+    pub fn finish(mut self) -> String {
+        while !self.open.is_empty() {
+            self.close();
+        }
+        self.buf
+    }
+
+    fn indent(&mut self) {
+        self.buf.push_str(&"  ".repeat(self.open.len()));
+    }
+
+    fn attrs(attrs: &[(&str, &str)]) -> String {
+        attrs
+            .iter()
+            .map(|(key, value)| format!(" {key}=\"{}\"", escape(value)))
+            .collect()
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -2,7 +2,6 @@ mod update_version;
 
 use anyhow::{anyhow, Error};
 use eframe::egui::{self, Ui};
-use ltc::LTCFrame;
 use parking_lot::Mutex;
 
 use std::{
@@ -17,10 +16,11 @@ use std::{
 };
 
 use crate::{
-    edl_writer,
+    edl_writer::{self, dialect::Dialect},
+    export::ExportFormat,
     ltc_decoder::{
         config::{DevicesFromHost, LTCDevice, LTCHostId},
-        LTCListener,
+        DecodedFrame, LTCListener,
     },
     server::Server,
     state::{Logger, Opt, StoredOpts},
@@ -33,7 +33,7 @@ pub struct App {
     tx_stop_serv: mpsc::Sender<()>,
     tx_serv_stopped: mpsc::Sender<()>,
     rx_serv_stopped: mpsc::Receiver<()>,
-    tx_ltc_frame: Option<single_val_channel::Sender<LTCFrame>>,
+    tx_ltc_frame: Option<single_val_channel::Sender<DecodedFrame>>,
     server_handle: Option<JoinHandle<Result<(), Error>>>,
     is_current_version: Arc<AtomicBool>,
     opt: Opt,
@@ -347,6 +347,178 @@ impl App {
             });
     }
 
+    fn config_edl_dialect(&mut self, ui: &mut Ui) {
+        egui::ComboBox::from_label("EDL Dialect")
+            .selected_text(String::from(<&str>::from(self.opt.edl_dialect)))
+            .show_ui(ui, |ui| {
+                for dialect in Dialect::ALL {
+                    ui.selectable_value(
+                        &mut self.opt.edl_dialect,
+                        dialect,
+                        String::from(<&str>::from(dialect)),
+                    )
+                    .write_on_change(&self.opt, StoredOpts::EdlDialect);
+                }
+            });
+    }
+
+    fn config_rollover_edits(&mut self, ui: &mut Ui) {
+        ui.add(
+            egui::Slider::new(&mut self.opt.rollover_edits, 1..=9999).text("Edits Per EDL File"),
+        )
+        .write_on_change(&self.opt, StoredOpts::RolloverEdits);
+    }
+
+    fn config_reel_ids(&mut self, ui: &mut Ui) {
+        ui.add(
+            egui::TextEdit::multiline(&mut self.opt.reel_ids)
+                .desired_rows(2)
+                .hint_text("Reel IDs (one tape=REEL per line)"),
+        )
+        .write_on_change(&self.opt, StoredOpts::ReelIds);
+    }
+
+    fn config_source_timecodes(&mut self, ui: &mut Ui) {
+        // the lock is let go before storing the setting, which takes it again
+        let label = ui.add(
+            egui::TextEdit::multiline(&mut *self.opt.source_timecodes.lock())
+                .desired_rows(2)
+                .hint_text("Source Timecodes (one tape=start TC or tape=+/-offset per line)"),
+        );
+        label.write_on_change(&self.opt, StoredOpts::SourceTimecodes);
+    }
+
+    fn config_record_timecode(&mut self, ui: &mut Ui) {
+        egui::ComboBox::from_label("Record Timecode")
+            .selected_text(String::from(self.opt.record_timecode))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    &mut self.opt.record_timecode,
+                    edl_writer::RecordTimecode::Ltc,
+                    String::from(edl_writer::RecordTimecode::Ltc),
+                )
+                .write_on_change(&self.opt, StoredOpts::RecordTimecode);
+                ui.selectable_value(
+                    &mut self.opt.record_timecode,
+                    edl_writer::RecordTimecode::Continuous,
+                    String::from(edl_writer::RecordTimecode::Continuous),
+                )
+                .write_on_change(&self.opt, StoredOpts::RecordTimecode);
+            });
+        if self.opt.record_timecode == edl_writer::RecordTimecode::Continuous {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.opt.record_start)
+                    .hint_text("Record Start (01:00:00:00)"),
+            )
+            .write_on_change(&self.opt, StoredOpts::RecordStart);
+        }
+    }
+
+    fn config_midnight(&mut self, ui: &mut Ui) {
+        egui::ComboBox::from_label("Past Midnight")
+            .selected_text(String::from(self.opt.midnight))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    &mut self.opt.midnight,
+                    edl_writer::Midnight::Wrap,
+                    String::from(edl_writer::Midnight::Wrap),
+                )
+                .write_on_change(&self.opt, StoredOpts::Midnight);
+                ui.selectable_value(
+                    &mut self.opt.midnight,
+                    edl_writer::Midnight::OffsetRecord,
+                    String::from(edl_writer::Midnight::OffsetRecord),
+                )
+                .write_on_change(&self.opt, StoredOpts::Midnight);
+            });
+    }
+
+    fn config_pause_gap(&mut self, ui: &mut Ui) {
+        egui::ComboBox::from_label("Pause Gap")
+            .selected_text(String::from(self.opt.pause_gap))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    &mut self.opt.pause_gap,
+                    edl_writer::PauseGap::LeaveOut,
+                    String::from(edl_writer::PauseGap::LeaveOut),
+                )
+                .write_on_change(&self.opt, StoredOpts::PauseGap);
+                ui.selectable_value(
+                    &mut self.opt.pause_gap,
+                    edl_writer::PauseGap::Black,
+                    String::from(edl_writer::PauseGap::Black),
+                )
+                .write_on_change(&self.opt, StoredOpts::PauseGap);
+            });
+    }
+
+    fn config_handles(&mut self, ui: &mut Ui) {
+        ui.add(egui::Slider::new(&mut self.opt.head_handle, 0..=120).text("Head Handle"))
+            .write_on_change(&self.opt, StoredOpts::HeadHandle);
+        ui.add(egui::Slider::new(&mut self.opt.tail_handle, 0..=120).text("Tail Handle"))
+            .write_on_change(&self.opt, StoredOpts::TailHandle);
+        egui::ComboBox::from_label("Handle Unit")
+            .selected_text(String::from(self.opt.handle_unit))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    &mut self.opt.handle_unit,
+                    edl_writer::HandleUnit::Frames,
+                    String::from(edl_writer::HandleUnit::Frames),
+                )
+                .write_on_change(&self.opt, StoredOpts::HandleUnit);
+                ui.selectable_value(
+                    &mut self.opt.handle_unit,
+                    edl_writer::HandleUnit::Seconds,
+                    String::from(edl_writer::HandleUnit::Seconds),
+                )
+                .write_on_change(&self.opt, StoredOpts::HandleUnit);
+            });
+    }
+
+    fn config_double_taps(&mut self, ui: &mut Ui) {
+        ui.add(
+            egui::Slider::new(&mut self.opt.min_edit_frames, 0..=30)
+                .text("Min Edit Length (frames)"),
+        )
+        .write_on_change(&self.opt, StoredOpts::MinEditFrames);
+        ui.checkbox(
+            &mut self.opt.merge_same_source,
+            "Merge Same-Source Consecutive Edits",
+        )
+        .write_on_change(&self.opt, StoredOpts::MergeSameSource);
+    }
+
+    fn config_export_formats(&mut self, ui: &mut Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Exports");
+            for format in ExportFormat::ALL {
+                let mut checked = self.opt.export_formats.contains(&format);
+                let checkbox = ui.checkbox(&mut checked, String::from(<&str>::from(format)));
+                if checkbox.changed() {
+                    match checked {
+                        true => self.opt.export_formats.push(format),
+                        false => self.opt.export_formats.retain(|f| *f != format),
+                    }
+                }
+                checkbox.write_on_change(&self.opt, StoredOpts::ExportFormats);
+            }
+        });
+    }
+
+    fn config_chapters(&mut self, ui: &mut Ui) {
+        ui.add(
+            egui::Slider::new(&mut self.opt.chapter_min_secs, 0..=60)
+                .text("Min Chapter Length (secs)"),
+        )
+        .write_on_change(&self.opt, StoredOpts::ChapterMinSecs);
+        ui.add(
+            egui::TextEdit::multiline(&mut self.opt.source_labels)
+                .desired_rows(2)
+                .hint_text("Source Labels (one tape=label per line)"),
+        )
+        .write_on_change(&self.opt, StoredOpts::SourceLabels);
+    }
+
     fn config_tcp_port(&mut self, ui: &mut Ui) {
         ui.add(egui::Slider::new(&mut self.opt.port, 3000..=9999).text("TCP Port"))
             .write_on_change(&self.opt, StoredOpts::Port);
@@ -418,6 +590,28 @@ impl eframe::App for App {
                 ui.add_space(space);
                 self.config_ntfs(ui);
                 ui.add_space(space);
+                self.config_edl_dialect(ui);
+                ui.add_space(space);
+                self.config_rollover_edits(ui);
+                ui.add_space(space);
+                self.config_reel_ids(ui);
+                ui.add_space(space);
+                self.config_source_timecodes(ui);
+                ui.add_space(space);
+                self.config_record_timecode(ui);
+                ui.add_space(space);
+                self.config_midnight(ui);
+                ui.add_space(space);
+                self.config_pause_gap(ui);
+                ui.add_space(space);
+                self.config_handles(ui);
+                ui.add_space(space);
+                self.config_double_taps(ui);
+                ui.add_space(space);
+                self.config_export_formats(ui);
+                ui.add_space(space);
+                self.config_chapters(ui);
+                ui.add_space(space);
                 self.config_tcp_port(ui);
                 ui.add_space(space);
                 ui.separator();
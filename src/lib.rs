@@ -1,4 +1,5 @@
 pub mod edl_writer;
+pub mod export;
 pub mod gui;
 pub mod ltc_decoder;
 pub mod server;
@@ -1,15 +1,21 @@
 pub mod config;
+pub mod motion;
 
 use anyhow::{anyhow, Context, Error};
 use cpal::traits::{DeviceTrait, StreamTrait};
 use ltc::{LTCDecoder, LTCFrame};
 use num_traits::cast::AsPrimitive;
-use vtc::{FramerateParseError, Timecode, TimecodeParseError};
+use vtc::{Framerate, FramerateParseError, Timecode, TimecodeParseError};
 
 use std::{collections::VecDeque, sync::mpsc, thread, time::Duration};
 
 use crate::{
-    ltc_decoder::config::{Device, LTCDevice},
+    edl_writer::edit_queue::Motion,
+    edl_writer::midnight,
+    ltc_decoder::{
+        config::{Device, LTCDevice},
+        motion::{LTCMotion, SpeedMeter},
+    },
     state::Opt,
     utils::single_val_channel::{self, ChannelErr},
 };
@@ -57,7 +63,7 @@ impl LTCListener {
     }
 
     pub fn listen(self) -> Result<DecodeHandlers, Error> {
-        let (frame_sender, frame_recv) = single_val_channel::channel::<LTCFrame>();
+        let (frame_sender, frame_recv) = single_val_channel::channel::<DecodedFrame>();
         let (decode_state_sender, decode_state_recv) = mpsc::channel::<DecodeState>();
         let (stop_listen_sender, stop_listen_recv) = mpsc::channel::<()>();
 
@@ -67,6 +73,7 @@ impl LTCListener {
             frame_sender.clone(),
             self.samples_per_frame(),
             self.input_channel,
+            framerate(&self.opt)?,
         );
 
         let input_config = cpal::StreamConfig {
@@ -156,34 +163,49 @@ pub enum DecodeState {
     Off,
 }
 
+#[derive(Debug)]
+pub struct DecodedFrame {
+    frame: LTCFrame,
+    // set while LTC runs off play speed
+    motion: Option<LTCMotion>,
+}
+
 struct DecodeContext {
-    frame_recv_drain: single_val_channel::Receiver<LTCFrame>,
-    frame_sender: single_val_channel::Sender<LTCFrame>,
+    frame_recv_drain: single_val_channel::Receiver<DecodedFrame>,
+    frame_sender: single_val_channel::Sender<DecodedFrame>,
     decode_state_recv: mpsc::Receiver<DecodeState>,
     decode_state: DecodeState,
     samples_per_frame: f32,
     decoder: LTCDecoder,
     input_channel: InputChannel,
     iters_since_last_decode: u16,
+    rate: Framerate,
+    // samples written to the decoder since decoding started
+    samples: u64,
+    speed_meter: SpeedMeter,
 }
 
 impl DecodeContext {
     fn new(
-        frame_recv_drain: single_val_channel::Receiver<LTCFrame>,
+        frame_recv_drain: single_val_channel::Receiver<DecodedFrame>,
         decode_state_recv: mpsc::Receiver<DecodeState>,
-        frame_sender: single_val_channel::Sender<LTCFrame>,
+        frame_sender: single_val_channel::Sender<DecodedFrame>,
         samples_per_frame: f32,
         input_channel: InputChannel,
+        rate: Framerate,
     ) -> Self {
         DecodeContext {
             decoder: LTCDecoder::new(samples_per_frame, VecDeque::new()),
             decode_state: DecodeState::Off,
             iters_since_last_decode: 0,
+            samples: 0,
+            speed_meter: SpeedMeter::new(samples_per_frame),
             frame_recv_drain,
             decode_state_recv,
             frame_sender,
             samples_per_frame,
             input_channel,
+            rate,
         }
     }
 
@@ -191,14 +213,16 @@ impl DecodeContext {
         if let Ok(state) = self.decode_state_recv.try_recv() {
             let _ = self.frame_recv_drain.try_recv();
             self.decoder = LTCDecoder::new(self.samples_per_frame, VecDeque::new());
+            self.reset_speed();
             self.decode_state = state
         };
 
         if let DecodeState::On = self.decode_state {
             match self.write_to_decoder(data) {
-                Some(tc) => {
+                Some(frame) => {
                     self.iters_since_last_decode = 0;
-                    if let Err(e) = self.frame_sender.send(tc) {
+                    let motion = self.measure_motion(&frame);
+                    if let Err(e) = self.frame_sender.send(DecodedFrame { frame, motion }) {
                         log::error!("Error setting current frame state: {}", e);
                     };
                 }
@@ -210,6 +234,7 @@ impl DecodeContext {
                     // drain it.
                     if self.iters_since_last_decode > 30 {
                         self.decoder = LTCDecoder::new(self.samples_per_frame, VecDeque::new());
+                        self.reset_speed();
                         self.iters_since_last_decode = 0;
                     } else {
                         self.iters_since_last_decode += 1;
@@ -221,6 +246,7 @@ impl DecodeContext {
 
     fn write_to_decoder<T: AsPrimitive<f32>>(&mut self, input: &[T]) -> Option<LTCFrame> {
         let input = self.parse_mono_input_from_channel(input);
+        self.samples += input.len() as u64;
         if self.decoder.write_samples(&input) {
             self.decoder.into_iter().next()
         } else {
@@ -228,6 +254,17 @@ impl DecodeContext {
         }
     }
 
+    // compares the LTC frames decoded against the frames of audio they were decoded over
+    fn measure_motion(&mut self, frame: &LTCFrame) -> Option<LTCMotion> {
+        let timecode = Timecode::with_frames(frame.format_time(), self.rate).ok()?;
+        self.speed_meter.measure(self.samples, timecode)
+    }
+
+    fn reset_speed(&mut self) {
+        self.samples = 0;
+        self.speed_meter.reset();
+    }
+
     fn parse_mono_input_from_channel<T: AsPrimitive<f32>>(&self, input: &[T]) -> Vec<f32> {
         input
             .chunks(self.input_channel.device_channels)
@@ -238,8 +275,8 @@ impl DecodeContext {
 
 #[derive(Clone)]
 pub struct DecodeHandlers {
-    pub tx_ltc_frame: single_val_channel::Sender<LTCFrame>,
-    pub rx_ltc_frame: single_val_channel::Receiver<LTCFrame>,
+    pub tx_ltc_frame: single_val_channel::Sender<DecodedFrame>,
+    pub rx_ltc_frame: single_val_channel::Receiver<DecodedFrame>,
     pub tx_decode_state: mpsc::Sender<DecodeState>,
     pub tx_stop_listen: mpsc::Sender<()>,
     opt: Opt,
@@ -247,8 +284,8 @@ pub struct DecodeHandlers {
 
 impl DecodeHandlers {
     fn new(
-        tx_ltc_frame: single_val_channel::Sender<LTCFrame>,
-        rx_ltc_frame: single_val_channel::Receiver<LTCFrame>,
+        tx_ltc_frame: single_val_channel::Sender<DecodedFrame>,
+        rx_ltc_frame: single_val_channel::Receiver<DecodedFrame>,
         tx_decode_state: mpsc::Sender<DecodeState>,
         tx_stop_listen: mpsc::Sender<()>,
         opt: Opt,
@@ -262,19 +299,19 @@ impl DecodeHandlers {
         }
     }
 
-    pub fn try_recv_frame(&self) -> Result<Timecode, DecodeErr> {
-        Ok(self.rx_ltc_frame.try_recv()?.into_timecode(&self.opt)?)
+    pub fn try_recv_frame(&self) -> Result<LTCTimecode, DecodeErr> {
+        Ok(self.rx_ltc_frame.try_recv()?.into_ltc_timecode(&self.opt)?)
     }
 
-    pub fn recv_frame(&self) -> Result<Timecode, DecodeErr> {
-        Ok(self.rx_ltc_frame.recv()?.into_timecode(&self.opt)?)
+    pub fn recv_frame(&self) -> Result<LTCTimecode, DecodeErr> {
+        Ok(self.rx_ltc_frame.recv()?.into_ltc_timecode(&self.opt)?)
     }
 
-    pub fn recv_frame_timeout(&self, timeout: Duration) -> Result<Timecode, DecodeErr> {
+    pub fn recv_frame_timeout(&self, timeout: Duration) -> Result<LTCTimecode, DecodeErr> {
         Ok(self
             .rx_ltc_frame
             .recv_timeout(timeout)?
-            .into_timecode(&self.opt)?)
+            .into_ltc_timecode(&self.opt)?)
     }
 
     pub fn decode_on(&self) -> Result<(), Error> {
@@ -333,22 +370,61 @@ impl From<ChannelErr> for DecodeErr {
     }
 }
 
+// the record timecode of a decoded frame, with the source it's replaying while LTC runs off
+// play speed
+#[derive(Debug, Clone, Copy)]
+pub struct LTCTimecode {
+    pub timecode: Timecode,
+    pub motion: Option<Motion>,
+}
+
+impl DecodedFrame {
+    fn into_ltc_timecode(self, opt: &Opt) -> Result<LTCTimecode, Error> {
+        LTCTimecode::measured(self.frame.into_timecode(opt)?, self.motion)
+    }
+}
+
+impl LTCTimecode {
+    // the LTC frame decoded with the motion measured up to it
+    fn measured(timecode: Timecode, motion: Option<LTCMotion>) -> Result<Self, Error> {
+        match motion {
+            None => Ok(LTCTimecode {
+                timecode,
+                motion: None,
+            }),
+            Some(LTCMotion {
+                speed,
+                record_frames,
+            }) => Ok(LTCTimecode {
+                // counting on past midnight, as the frames it was measured over
+                timecode: Timecode::with_frames(record_frames, timecode.rate())
+                    .map(midnight::wrap)
+                    .map_err(|e| Error::msg(e.into_msg()))?,
+                motion: Some(Motion {
+                    speed,
+                    source_in: timecode,
+                }),
+            }),
+        }
+    }
+}
+
 pub trait TransformToTimecode {
     fn into_timecode(self, opt: &Opt) -> Result<Timecode, Error>;
 }
 
 impl TransformToTimecode for LTCFrame {
     fn into_timecode(self, opt: &Opt) -> Result<Timecode, Error> {
-        vtc::Timecode::with_frames(
-            self.format_time(),
-            vtc::Framerate::with_playback(opt.fps, opt.ntsc.as_vtc())
-                .map_err(|e| Error::msg(e.into_msg()))?,
-        )
-        .map_err(|e| Error::msg(e.into_msg()))
+        vtc::Timecode::with_frames(self.format_time(), framerate(opt)?)
+            .map_err(|e| Error::msg(e.into_msg()))
     }
 }
 
-trait TCError {
+pub(crate) fn framerate(opt: &Opt) -> Result<Framerate, Error> {
+    Framerate::with_playback(opt.fps, opt.ntsc.as_vtc()).map_err(|e| Error::msg(e.into_msg()))
+}
+
+pub(crate) trait TCError {
     fn into_msg(self) -> String;
 }
 
@@ -373,3 +449,43 @@ impl TCError for FramerateParseError {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::edl_writer::edit_queue::{Edit, EditQueue};
+    use vtc::rates;
+
+    #[test]
+    fn edit_across_midnight() {
+        let samples_per_frame = 2000;
+        let mut meter = SpeedMeter::new(samples_per_frame as f32);
+        let mut queue = EditQueue::new();
+        let start = Timecode::with_frames("23:59:59:16", rates::F24).unwrap();
+        // LTC at play speed, cutting to tape a before midnight and to tape b after it
+        let cuts = [(0, None), (4, Some("a")), (12, Some("b"))];
+        for frame in 0..=12 {
+            let tc =
+                midnight::wrap(Timecode::with_frames(start.frames() + frame, rates::F24).unwrap());
+            let motion = meter.measure(frame as u64 * samples_per_frame, tc);
+            let ltc = LTCTimecode::measured(tc, motion).unwrap();
+            assert!(ltc.motion.is_none());
+            if let Some((_, tape)) = cuts.iter().find(|(at, _)| *at == frame) {
+                queue
+                    .push(Edit {
+                        source_tape: tape.map(String::from),
+                        ..Edit::black(ltc.timecode)
+                    })
+                    .unwrap();
+            }
+        }
+        queue.pop_closed();
+        let edit = queue.pop_closed().unwrap();
+        assert_eq!(edit.source_tape.as_deref(), Some("a"));
+        assert!(edit.motion.is_none());
+        assert_eq!(
+            queue.front().unwrap().timecode.frames() - edit.timecode.frames(),
+            8
+        );
+    }
+}
@@ -0,0 +1,139 @@
+// LTC off play speed, ie. from a replay deck in slow motion or reverse, is the source being
+// replayed. Its speed is measured over the last few frames decoded, against the frames of audio
+// they were decoded over. Frames are counted on past midnight, and LTC jumping to another point
+// starts the measurement over rather than reading as a burst of speed.
+
+use vtc::Timecode;
+
+use std::collections::VecDeque;
+
+use crate::edl_writer::midnight;
+
+// frames decoded over which the LTC speed is measured, about a third of a second
+const SPEED_WINDOW: usize = 8;
+// percent either side of play speed still taken as play speed
+const SPEED_TOLERANCE: f32 = 3.0;
+// times play speed past any motion effect an EDL can hold, LTC moving faster has jumped
+const JUMP_SPEED: f32 = 50.0;
+
+// The record timecode keeps running at play speed from the last frame decoded at play speed,
+// counted by the samples since.
+#[derive(Debug, Clone, Copy)]
+pub struct LTCMotion {
+    pub speed: f32,
+    pub record_frames: i64,
+}
+
+#[derive(Debug)]
+pub struct SpeedMeter {
+    samples_per_frame: f32,
+    // sample count and LTC frame of the last frames decoded, counting on past midnight
+    decoded: VecDeque<(u64, Timecode)>,
+    // sample count and frame of the last frame decoded at play speed
+    play_speed_anchor: Option<(u64, i64)>,
+}
+
+impl SpeedMeter {
+    pub fn new(samples_per_frame: f32) -> Self {
+        SpeedMeter {
+            samples_per_frame,
+            decoded: VecDeque::with_capacity(SPEED_WINDOW),
+            play_speed_anchor: None,
+        }
+    }
+
+    // `samples` is the sample count the frame was decoded at
+    pub fn measure(&mut self, samples: u64, timecode: Timecode) -> Option<LTCMotion> {
+        let timecode = match self.decoded.back() {
+            Some(&(last_sample, last)) => {
+                let timecode = midnight::unwrap(timecode, last);
+                let elapsed = samples.saturating_sub(last_sample) as f32 / self.samples_per_frame;
+                let moved = (timecode.frames() - last.frames()).abs() as f32;
+                if moved > (elapsed + 1.0) * JUMP_SPEED {
+                    self.reset();
+                }
+                timecode
+            }
+            None => timecode,
+        };
+        if self.decoded.len() == SPEED_WINDOW {
+            self.decoded.pop_front();
+        }
+        self.decoded.push_back((samples, timecode));
+        let frames = timecode.frames();
+
+        let speed = match (self.decoded.front(), self.decoded.back()) {
+            (Some((first_sample, first)), Some((last_sample, last)))
+                if self.decoded.len() == SPEED_WINDOW =>
+            {
+                let elapsed = (last_sample - first_sample) as f32 / self.samples_per_frame;
+                ((last.frames() - first.frames()) as f32 / elapsed * 100.0).round()
+            }
+            _ => 100.0,
+        };
+        if (speed - 100.0).abs() <= SPEED_TOLERANCE {
+            self.play_speed_anchor = Some((samples, frames));
+            return None;
+        }
+        let (anchor_sample, anchor_frame) =
+            *self.play_speed_anchor.get_or_insert((samples, frames));
+        Some(LTCMotion {
+            speed,
+            record_frames: anchor_frame
+                + ((samples - anchor_sample) as f32 / self.samples_per_frame) as i64,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.decoded.clear();
+        self.play_speed_anchor = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use vtc::rates;
+
+    const SAMPLES_PER_FRAME: u64 = 2000;
+
+    // decodes `count` frames from `start` at play speed, none measured off it
+    fn decode(meter: &mut SpeedMeter, samples: &mut u64, start: &str, count: usize) {
+        let start = Timecode::with_frames(start, rates::F24).unwrap();
+        for i in 0..count {
+            *samples += SAMPLES_PER_FRAME;
+            let tc = midnight::wrap(
+                Timecode::with_frames(start.frames() + i as i64, rates::F24).unwrap(),
+            );
+            assert!(meter.measure(*samples, tc).is_none(), "{}", tc.timecode());
+        }
+    }
+
+    #[test]
+    fn play_speed_through_midnight() {
+        let mut meter = SpeedMeter::new(SAMPLES_PER_FRAME as f32);
+        let mut samples = 0;
+        decode(&mut meter, &mut samples, "23:59:59:18", 16);
+    }
+
+    #[test]
+    fn ltc_jump() {
+        let mut meter = SpeedMeter::new(SAMPLES_PER_FRAME as f32);
+        let mut samples = 0;
+        decode(&mut meter, &mut samples, "10:00:00:00", 10);
+        // cued to another point, forwards then back
+        decode(&mut meter, &mut samples, "14:30:00:00", 10);
+        decode(&mut meter, &mut samples, "02:00:00:00", 10);
+
+        // slow motion is still measured after a jump
+        let start = Timecode::with_frames("05:00:00:00", rates::F24).unwrap();
+        let tc = |i: u64| Timecode::with_frames(start.frames() + i as i64, rates::F24).unwrap();
+        meter.measure(samples, start);
+        let motion = (1..SPEED_WINDOW as u64)
+            .map(|i| meter.measure(samples + i * SAMPLES_PER_FRAME * 2, tc(i)))
+            .last()
+            .flatten()
+            .unwrap();
+        assert_eq!(motion.speed, 50.0);
+    }
+}
@@ -3,9 +3,9 @@ use httparse::{Request as ReqParser, Status};
 use parking_lot::{Mutex, MutexGuard};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use vtc::Timecode;
 
 use std::{
+    fmt,
     io::{prelude::*, BufReader},
     net::{TcpListener, TcpStream},
     sync::{
@@ -17,9 +17,16 @@ use std::{
 };
 
 use crate::{
-    edl_writer::{edit_queue::Edit, AVChannels, EditType, Edl, Event, SourceTape},
-    ltc_decoder::{DecodeErr, DecodeHandlers},
-    state::Opt,
+    edl_writer::{
+        correction::EditPatch,
+        edit_queue::{Edit, Merge, Motion, OrderedEdit, Split},
+        marker::MarkerColor,
+        sidecar::EventOrigin,
+        source_tc::SourceTc,
+        track_name, AVChannels, EditType, Edl, Event, SourceTape, MAIN_TRACK,
+    },
+    ltc_decoder::{framerate, DecodeErr, DecodeHandlers, LTCTimecode},
+    state::{Opt, StoredOpts},
 };
 
 pub struct Server {
@@ -86,6 +93,7 @@ impl Server {
     }
 
     fn handle_connection(&mut self, mut socket: TcpStream, ctx: &mut Context) -> Result<(), Error> {
+        let origin = EventOrigin::new(socket.peer_addr().ok());
         let mut buf_reader = BufReader::new(&mut socket);
         let mut headers = [httparse::EMPTY_HEADER; 16];
         let mut headers = ReqParser::new(&mut headers);
@@ -93,7 +101,7 @@ impl Server {
         let res = buf_reader
             .fill_buf()
             .context("Unable to fill buffer")
-            .and_then(|buf| Request::new(&mut headers, buf))
+            .and_then(|buf| Request::new(&mut headers, buf, origin))
             .and_then(|mut req| req.route(ctx))
             .and_then(|res| res.json())
             .unwrap_or_else(|e| {
@@ -142,12 +150,17 @@ enum EdlRecordingState {
     Started,
     Stopped,
     Waiting,
+    // recording is on hold, ie. over a commercial break, while LTC is still decoded
+    Paused,
 }
 
-#[allow(clippy::large_enum_variant)]
+// each event is paired with the name of the EDL file it was written to
 enum EditBody {
-    Event(Event),
-    Edits(Vec<Event>),
+    // the events closed by a logged edit, two when it brings split video and audio back together
+    Log(Vec<(Event, String)>),
+    Edits(Vec<(Event, String)>),
+    // the edit a correction removed, if any, and the edits it rewrote
+    Corrected(Option<Box<Event>>, Vec<(Event, String)>),
 }
 
 #[derive(Serialize, Debug)]
@@ -155,25 +168,64 @@ enum EditBody {
 struct ResBody {
     recording_state: EdlRecordingState,
     edit: Option<Event>,
+    // the audio line closed alongside `edit` when video and audio were split
+    split_edit: Option<Event>,
     final_edits: Option<Vec<Event>>,
+    // the edit removed by a delete
+    deleted_edit: Option<Event>,
+    // the edits rewritten by a correction, renumbered, from the first it changed
+    corrected_edits: Option<Vec<Event>>,
+    // the EDL file of each returned edit, in the same order
+    edl_files: Option<Vec<String>>,
+    // a double-tap folded into the open edit rather than logged
+    merged: Option<Merge>,
 }
 
 impl ResBody {
     fn new(recording_state: EdlRecordingState, edit_or_edits: Option<EditBody>) -> Self {
-        let (edit, final_edits) = edit_or_edits
-            .map(|eb| match eb {
-                EditBody::Event(e) => (Some(e), None),
-                EditBody::Edits(es) => (None, Some(es)),
-            })
-            .unwrap_or((None, None));
-        ResBody {
+        let mut res = ResBody {
             recording_state,
-            edit,
-            final_edits,
+            edit: None,
+            split_edit: None,
+            final_edits: None,
+            deleted_edit: None,
+            corrected_edits: None,
+            edl_files: None,
+            merged: None,
+        };
+        match edit_or_edits {
+            Some(EditBody::Log(es)) => {
+                let (es, files): (Vec<_>, _) = es.into_iter().unzip();
+                let mut es = es.into_iter();
+                res.edit = es.next();
+                res.split_edit = es.next();
+                res.edl_files = Some(files);
+            }
+            Some(EditBody::Edits(es)) => {
+                let (es, files) = es.into_iter().unzip();
+                res.final_edits = Some(es);
+                res.edl_files = Some(files);
+            }
+            Some(EditBody::Corrected(deleted, es)) => {
+                let (es, files) = es.into_iter().unzip();
+                res.deleted_edit = deleted.map(|deleted| *deleted);
+                res.corrected_edits = Some(es);
+                res.edl_files = Some(files);
+            }
+            None => (),
         }
+        res
     }
 }
 
+// the edits waiting for their out point on a track, both sides while video and audio are split
+#[derive(Serialize, Debug)]
+struct OpenEditBody {
+    recording_state: EdlRecordingState,
+    track: String,
+    open_edits: Vec<OrderedEdit>,
+}
+
 #[derive(Debug)]
 struct Response {
     content: Value,
@@ -199,10 +251,15 @@ pub struct Request<'req> {
     path: Option<&'req str>,
     header_offset: usize,
     buffer: &'req [u8],
+    origin: EventOrigin,
 }
 
 impl<'req> Request<'req> {
-    fn new(req_parser: &'req mut ReqParser<'req, 'req>, buffer: &'req [u8]) -> Result<Self, Error> {
+    fn new(
+        req_parser: &'req mut ReqParser<'req, 'req>,
+        buffer: &'req [u8],
+        origin: EventOrigin,
+    ) -> Result<Self, Error> {
         let header_offset = match req_parser.parse(buffer) {
             Ok(Status::Complete(header_offset)) => Ok(header_offset),
 
@@ -217,6 +274,7 @@ impl<'req> Request<'req> {
             path: req_parser.path,
             header_offset,
             buffer,
+            origin,
         })
     }
 
@@ -229,8 +287,14 @@ impl<'req> Request<'req> {
                 Some("/end") => self.handle_end(ctx).inspect_err(|_| {
                     ctx.lock().set_rec_state(EdlRecordingState::Started);
                 }),
+                Some("/pause") => self.handle_pause(ctx),
+                Some("/resume") => self.handle_resume(ctx).inspect_err(|_| {
+                    ctx.lock().set_rec_state(EdlRecordingState::Paused);
+                }),
                 Some("/log") => self.handle_log(ctx),
                 Some("/select-src") => self.handle_select_src(ctx),
+                Some("/source-tc") => self.handle_source_tc(ctx),
+                Some("/marker") => self.handle_marker(ctx),
                 _ => Ok(not_found()),
             },
             Some("GET") => match self.path {
@@ -238,8 +302,27 @@ impl<'req> Request<'req> {
                     ResBody::new(ctx.lock().rec_state, None).try_into_200()
                 }
                 Some("/SIGKILL") => Ok(kill_server()),
-                _ => Ok(not_found()),
+                _ => match self.path.and_then(EditPath::parse) {
+                    Some(edit_path) if edit_path.target == EditTarget::Open => {
+                        self.handle_open(ctx, edit_path)
+                    }
+                    _ => Ok(not_found()),
+                },
             },
+            Some(method @ ("DELETE" | "PATCH")) => {
+                match (method, self.path.and_then(EditPath::parse)) {
+                    // the open edit isn't written yet, so there's nothing to delete
+                    ("DELETE", Some(edit_path)) if edit_path.target == EditTarget::Open => {
+                        Ok(not_found())
+                    }
+                    ("DELETE", Some(edit_path)) => self.handle_delete(ctx, edit_path),
+                    (_, Some(edit_path)) if edit_path.target == EditTarget::Open => {
+                        self.handle_open(ctx, edit_path)
+                    }
+                    (_, Some(edit_path)) => self.handle_patch(ctx, edit_path),
+                    (_, None) => Ok(not_found()),
+                }
+            }
             _ => Ok(not_found()),
         }
     }
@@ -252,11 +335,7 @@ impl<'req> Request<'req> {
                 log::info!("EDL recording start requested. Waiting for LTC signal.");
 
                 ctx_guard.decode_handlers.decode_on()?;
-                ctx_guard.edl = Some(Edl::new(
-                    &ctx_guard.opt.dir,
-                    &ctx_guard.opt.title,
-                    ctx_guard.opt.ntsc,
-                )?);
+                ctx_guard.edl = Some(Edl::new(&ctx_guard.opt)?);
 
                 let mut edit_req = self
                     .body()?
@@ -275,7 +354,9 @@ impl<'req> Request<'req> {
                         StartErr::Anyhow(e) => Err(e),
                     })
             }
-            s @ EdlRecordingState::Started | s @ EdlRecordingState::Waiting => {
+            s @ EdlRecordingState::Started
+            | s @ EdlRecordingState::Waiting
+            | s @ EdlRecordingState::Paused => {
                 log::warn!("Recording has already started. You cannot start in this state.");
                 ResBody::new(s, None).try_into_202()
             }
@@ -292,10 +373,13 @@ impl<'req> Request<'req> {
                     .body()?
                     .context("Request does not contain a body")?
                     .expect_edit()?
-                    .try_log_final_edit(&mut ctx_guard)?;
+                    .try_log_final_edit(&mut ctx_guard, &self.origin)?;
 
                 ctx_guard.decode_handlers.decode_off()?;
-                ctx_guard.edl = None;
+                if let Some(edl) = ctx_guard.edl.take() {
+                    edl.write_exports()
+                        .unwrap_or_else(|e| log::error!("Could not write exports: {:#}", e));
+                }
                 let rec_state = ctx_guard.set_rec_state(EdlRecordingState::Stopped);
                 log::info!("EDL recording ended.");
 
@@ -307,6 +391,17 @@ impl<'req> Request<'req> {
                 log::info!("EDL recording ended");
                 ResBody::new(rec_state, None).try_into_200()
             }
+            // every track was cut to black by the pause, which is where the recording ends
+            EdlRecordingState::Paused => {
+                ctx_guard.decode_handlers.decode_off()?;
+                if let Some(edl) = ctx_guard.edl.take() {
+                    edl.write_exports()
+                        .unwrap_or_else(|e| log::error!("Could not write exports: {:#}", e));
+                }
+                let rec_state = ctx_guard.set_rec_state(EdlRecordingState::Stopped);
+                log::info!("EDL recording ended while paused.");
+                ResBody::new(rec_state, Some(EditBody::Edits(Vec::new()))).try_into_200()
+            }
             s @ EdlRecordingState::Stopped => {
                 log::warn!("Recording not yet started!");
                 ResBody::new(s, None).try_into_202()
@@ -321,12 +416,83 @@ impl<'req> Request<'req> {
                 .body()?
                 .context("Request does not contain a body")?
                 .expect_edit()?
-                .try_log_edit(&mut ctx_guard)?
+                .try_log_edit(&mut ctx_guard, &self.origin)?
                 .try_into_200(),
             s @ EdlRecordingState::Stopped | s @ EdlRecordingState::Waiting => {
                 log::warn!("Recording not yet started!");
                 ResBody::new(s, None).try_into_202()
             }
+            s @ EdlRecordingState::Paused => {
+                log::warn!("Recording is paused!");
+                ResBody::new(s, None).try_into_202()
+            }
+        }
+    }
+
+    // closes the open edit on every track with a cut to black, or the transition in the body
+    fn handle_pause(&mut self, ctx: &mut Context) -> Result<Response, Error> {
+        let mut ctx_guard = ctx.lock();
+        match ctx_guard.rec_state {
+            EdlRecordingState::Started => {
+                let edits = self
+                    .body()?
+                    .context("Request does not contain a body")?
+                    .expect_edit()?
+                    .try_log_final_edit(&mut ctx_guard, &self.origin)?;
+                let rec_state = ctx_guard.set_rec_state(EdlRecordingState::Paused);
+                log::info!("EDL recording paused.");
+                ResBody::new(rec_state, Some(EditBody::Edits(edits))).try_into_200()
+            }
+            s @ EdlRecordingState::Paused => {
+                log::warn!("Recording is already paused!");
+                ResBody::new(s, None).try_into_202()
+            }
+            s @ EdlRecordingState::Stopped | s @ EdlRecordingState::Waiting => {
+                log::warn!("Recording not yet started!");
+                ResBody::new(s, None).try_into_202()
+            }
+        }
+    }
+
+    // logs the edit in the body at the current LTC frame, after the gap the pause left. The
+    // recording stays paused, with each track as it was, if the edit can't be logged.
+    fn handle_resume(&mut self, ctx: &mut Context) -> Result<Response, Error> {
+        let mut ctx_guard = ctx.lock();
+        match ctx_guard.rec_state {
+            EdlRecordingState::Paused => {
+                let mut edit_req = self
+                    .body()?
+                    .context("Request does not contain a body")?
+                    .expect_edit()?;
+                let edit = edit_req
+                    .map_source_from_ctx(&mut ctx_guard)
+                    .take_current_edit(&mut ctx_guard)?;
+                let paused = ctx_guard
+                    .edl
+                    .as_mut()
+                    .context("EDL file does not exist")?
+                    .resume();
+                ctx_guard.set_rec_state(EdlRecordingState::Started);
+                match edit_req.try_log(&mut ctx_guard, edit, &self.origin) {
+                    Ok(res_body) => {
+                        log::info!("EDL recording resumed.");
+                        res_body.try_into_200()
+                    }
+                    Err(e) => {
+                        if let Some(edl) = ctx_guard.edl.as_mut() {
+                            edl.stay_paused(paused);
+                        }
+                        ctx_guard.set_rec_state(EdlRecordingState::Paused);
+                        Err(e)
+                    }
+                }
+            }
+            s @ EdlRecordingState::Started
+            | s @ EdlRecordingState::Stopped
+            | s @ EdlRecordingState::Waiting => {
+                log::warn!("Recording is not paused!");
+                ResBody::new(s, None).try_into_202()
+            }
         }
     }
 
@@ -337,6 +503,133 @@ impl<'req> Request<'req> {
             .try_select_src(ctx)
     }
 
+    fn handle_source_tc(&mut self, ctx: &mut Context) -> Result<Response, Error> {
+        self.body()?
+            .context("Request does not contain a body")?
+            .expect_source_tc()?
+            .try_set_source_tc(ctx)
+    }
+
+    fn handle_marker(&mut self, ctx: &mut Context) -> Result<Response, Error> {
+        let mut ctx_guard = ctx.lock();
+        match ctx_guard.rec_state {
+            EdlRecordingState::Started => self
+                .body()?
+                .context("Request does not contain a body")?
+                .expect_marker()?
+                .try_add_marker(&mut ctx_guard, &self.origin),
+            s @ EdlRecordingState::Stopped | s @ EdlRecordingState::Waiting => {
+                log::warn!("Recording not yet started!");
+                ResBody::new(s, None).try_into_202()
+            }
+            s @ EdlRecordingState::Paused => {
+                log::warn!("Recording is paused!");
+                ResBody::new(s, None).try_into_202()
+            }
+        }
+    }
+
+    fn handle_delete(&mut self, ctx: &mut Context, edit_path: EditPath) -> Result<Response, Error> {
+        let mut ctx_guard = ctx.lock();
+        match ctx_guard.rec_state {
+            EdlRecordingState::Started | EdlRecordingState::Paused => {
+                let track = edit_path.track.as_deref();
+                let n = edit_path.target.try_into()?;
+                let edl = ctx_guard.edl.as_mut().context("EDL file does not exist")?;
+                if !edl.has_event(track, n) {
+                    log::warn!("No {} edit to delete", edit_path.target);
+                    return Ok(not_found());
+                }
+                let (deleted, edits) = edl.delete_event(track, n, &self.origin)?;
+                log::info!("Deleted {} edit", edit_path.target);
+                ResBody::new(
+                    ctx_guard.rec_state,
+                    Some(EditBody::Corrected(Some(Box::new(deleted)), edits)),
+                )
+                .try_into_200()
+            }
+            s @ EdlRecordingState::Stopped | s @ EdlRecordingState::Waiting => {
+                log::warn!("Recording not yet started!");
+                ResBody::new(s, None).try_into_202()
+            }
+        }
+    }
+
+    fn handle_patch(&mut self, ctx: &mut Context, edit_path: EditPath) -> Result<Response, Error> {
+        let mut ctx_guard = ctx.lock();
+        match ctx_guard.rec_state {
+            EdlRecordingState::Started | EdlRecordingState::Paused => {
+                let edit_patch = self
+                    .body()?
+                    .context("Request does not contain a body")?
+                    .expect_patch()?
+                    .try_into()?;
+                let track = edit_path.track.as_deref();
+                let n = edit_path.target.try_into()?;
+                let edl = ctx_guard.edl.as_mut().context("EDL file does not exist")?;
+                if !edl.has_event(track, n) {
+                    log::warn!("No {} edit to patch", edit_path.target);
+                    return Ok(not_found());
+                }
+                let edits = edl.patch_event(track, n, &edit_patch, &self.origin)?;
+                log::info!("Patched {} edit", edit_path.target);
+                ResBody::new(ctx_guard.rec_state, Some(EditBody::Corrected(None, edits)))
+                    .try_into_200()
+            }
+            s @ EdlRecordingState::Stopped | s @ EdlRecordingState::Waiting => {
+                log::warn!("Recording not yet started!");
+                ResBody::new(s, None).try_into_202()
+            }
+        }
+    }
+
+    // shows the edits waiting for their out point on GET, and amends the last one opened on PATCH
+    fn handle_open(&mut self, ctx: &mut Context, edit_path: EditPath) -> Result<Response, Error> {
+        let mut ctx_guard = ctx.lock();
+        match ctx_guard.rec_state {
+            EdlRecordingState::Started => {
+                let track = edit_path.track.as_deref();
+                let edl = ctx_guard.edl.as_ref().context("EDL file does not exist")?;
+                if !edl.has_track(track) {
+                    log::warn!("No edits on track {}", track.unwrap_or(MAIN_TRACK));
+                    return Ok(not_found());
+                }
+                let open_edits = match self.method {
+                    Some("PATCH") => {
+                        let edit_patch = self
+                            .body()?
+                            .context("Request does not contain a body")?
+                            .expect_patch()?
+                            .try_into()?;
+                        let edl = ctx_guard.edl.as_mut().context("EDL file does not exist")?;
+                        let open_edits = edl.amend_open_edit(track, &edit_patch)?;
+                        log::info!("Amended open edit");
+                        open_edits
+                    }
+                    _ => ctx_guard
+                        .edl
+                        .as_ref()
+                        .context("EDL file does not exist")?
+                        .open_edits(track)?,
+                };
+                let body = OpenEditBody {
+                    recording_state: ctx_guard.rec_state,
+                    track: track_name(track)?,
+                    open_edits,
+                };
+                Ok(Response::new(serde_json::to_value(body)?, StatusCode::S200))
+            }
+            s @ EdlRecordingState::Stopped | s @ EdlRecordingState::Waiting => {
+                log::warn!("Recording not yet started!");
+                ResBody::new(s, None).try_into_202()
+            }
+            s @ EdlRecordingState::Paused => {
+                log::warn!("Recording is paused!");
+                ResBody::new(s, None).try_into_202()
+            }
+        }
+    }
+
     fn body(&mut self) -> Result<Option<ReqBody>, Error> {
         let body_length = self
             .headers
@@ -369,12 +662,68 @@ impl<'req> Request<'req> {
     }
 }
 
+// the edit a correction is made to, by number across the whole recording
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EditTarget {
+    Last,
+    Number(usize),
+    // the edit waiting for its out point, not yet written
+    Open,
+}
+
+impl TryFrom<EditTarget> for Option<usize> {
+    type Error = Error;
+    fn try_from(value: EditTarget) -> Result<Self, Self::Error> {
+        match value {
+            EditTarget::Last => Ok(None),
+            EditTarget::Number(n) => Ok(Some(n)),
+            EditTarget::Open => Err(anyhow!("The open edit has not been written")),
+        }
+    }
+}
+
+impl fmt::Display for EditTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditTarget::Last => write!(f, "last"),
+            EditTarget::Number(n) => write!(f, "#{n}"),
+            EditTarget::Open => write!(f, "open"),
+        }
+    }
+}
+
+// `/edits/last`, `/edits/{n}` or `/edits/open`, with `?track=V2` for an edit on another track
+#[derive(Debug, PartialEq)]
+struct EditPath {
+    target: EditTarget,
+    track: Option<String>,
+}
+
+impl EditPath {
+    fn parse(path: &str) -> Option<Self> {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let target = match path.strip_prefix("/edits/")? {
+            "last" => EditTarget::Last,
+            "open" => EditTarget::Open,
+            n => EditTarget::Number(n.parse().ok().filter(|n| *n > 0)?),
+        };
+        let track = query
+            .split('&')
+            .find_map(|param| param.strip_prefix("track="))
+            .map(String::from);
+        Some(EditPath { target, track })
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "lowercase", tag = "req_type")]
 #[cfg_attr(test, derive(Serialize))]
 pub enum ReqBody {
     Event(EditRequestData),
     Source(SourceTapeRequestData),
+    SourceTc(SourceTcRequestData),
+    Marker(MarkerRequestData),
+    Patch(PatchRequestData),
 }
 
 impl ReqBody {
@@ -384,6 +733,15 @@ impl ReqBody {
             ReqBody::Event(_) => Err(anyhow!(
                 "Unexpected request type: expected source, got edit"
             )),
+            ReqBody::SourceTc(_) => Err(anyhow!(
+                "Unexpected request type: expected source, got sourcetc"
+            )),
+            ReqBody::Marker(_) => Err(anyhow!(
+                "Unexpected request type: expected source, got marker"
+            )),
+            ReqBody::Patch(_) => Err(anyhow!(
+                "Unexpected request type: expected source, got patch"
+            )),
         }
     }
 
@@ -393,6 +751,65 @@ impl ReqBody {
             ReqBody::Source(_) => Err(anyhow!(
                 "Unexpected request type: expected edit, got source"
             )),
+            ReqBody::SourceTc(_) => Err(anyhow!(
+                "Unexpected request type: expected edit, got sourcetc"
+            )),
+            ReqBody::Marker(_) => Err(anyhow!(
+                "Unexpected request type: expected edit, got marker"
+            )),
+            ReqBody::Patch(_) => Err(anyhow!("Unexpected request type: expected edit, got patch")),
+        }
+    }
+
+    fn expect_source_tc(self) -> Result<SourceTcRequestData, Error> {
+        match self {
+            ReqBody::SourceTc(src_tc) => Ok(src_tc),
+            ReqBody::Event(_) => Err(anyhow!(
+                "Unexpected request type: expected sourcetc, got edit"
+            )),
+            ReqBody::Source(_) => Err(anyhow!(
+                "Unexpected request type: expected sourcetc, got source"
+            )),
+            ReqBody::Marker(_) => Err(anyhow!(
+                "Unexpected request type: expected sourcetc, got marker"
+            )),
+            ReqBody::Patch(_) => Err(anyhow!(
+                "Unexpected request type: expected sourcetc, got patch"
+            )),
+        }
+    }
+
+    fn expect_marker(self) -> Result<MarkerRequestData, Error> {
+        match self {
+            ReqBody::Marker(marker) => Ok(marker),
+            ReqBody::Event(_) => Err(anyhow!(
+                "Unexpected request type: expected marker, got edit"
+            )),
+            ReqBody::Source(_) => Err(anyhow!(
+                "Unexpected request type: expected marker, got source"
+            )),
+            ReqBody::SourceTc(_) => Err(anyhow!(
+                "Unexpected request type: expected marker, got sourcetc"
+            )),
+            ReqBody::Patch(_) => Err(anyhow!(
+                "Unexpected request type: expected marker, got patch"
+            )),
+        }
+    }
+
+    fn expect_patch(self) -> Result<PatchRequestData, Error> {
+        match self {
+            ReqBody::Patch(patch) => Ok(patch),
+            ReqBody::Event(_) => Err(anyhow!("Unexpected request type: expected patch, got edit")),
+            ReqBody::Source(_) => Err(anyhow!(
+                "Unexpected request type: expected patch, got source"
+            )),
+            ReqBody::SourceTc(_) => Err(anyhow!(
+                "Unexpected request type: expected patch, got sourcetc"
+            )),
+            ReqBody::Marker(_) => Err(anyhow!(
+                "Unexpected request type: expected patch, got marker"
+            )),
         }
     }
 }
@@ -403,19 +820,37 @@ pub struct EditRequestData {
     pub(crate) edit_type: String,
     pub(crate) edit_duration_frames: Option<u32>,
     pub(crate) wipe_num: Option<u32>,
+    // the fade down at the end of a key, `edit_duration_frames` is its fade up
+    pub(crate) key_out_frames: Option<u32>,
     pub(crate) source_tape: Option<String>,
     pub(crate) av_channels: Option<AVChannels>,
+    // "video" or "audio" to change only that side, the other keeps its current source
+    pub(crate) split: Option<String>,
+    // percent of play speed the source plays at, overriding the speed LTC is decoded at
+    pub(crate) speed: Option<f32>,
+    // the track the edit is on, ie. "V2" for a graphics layer over the program on "V1"
+    pub(crate) track: Option<String>,
 }
 
 impl EditRequestData {
-    pub fn take_as_edit(&mut self, timecode: Timecode) -> Result<Edit, Error> {
+    pub fn take_as_edit(&mut self, ltc: LTCTimecode) -> Result<Edit, Error> {
+        let motion = match (self.speed, ltc.motion) {
+            (Some(speed), motion) => Some(Motion {
+                speed,
+                source_in: motion.map_or(ltc.timecode, |motion| motion.source_in),
+            }),
+            (None, motion) => motion,
+        };
         Ok(Edit {
             edit_type: self.edit_type.as_str().try_into()?,
             source_tape: self.source_tape.clone(),
             edit_duration_frames: self.edit_duration_frames,
             wipe_num: self.wipe_num.or(Some(1)),
+            key_out_frames: self.key_out_frames,
             av_channels: self.av_channels.unwrap_or_else(AVChannels::video_only),
-            timecode,
+            split: self.split.as_deref().map(Split::try_from).transpose()?,
+            motion,
+            timecode: ltc.timecode,
         })
     }
 }
@@ -431,21 +866,51 @@ impl EditRequestData {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }
     }
 
     // TODO: warn if source_tape or av_channels is None here
-    fn try_log_edit(&mut self, ctx_guard: &mut MutexGuard<ContextInner>) -> Result<ResBody, Error> {
+    fn try_log_edit(
+        &mut self,
+        ctx_guard: &mut MutexGuard<ContextInner>,
+        origin: &EventOrigin,
+    ) -> Result<ResBody, Error> {
         let edit = self
             .map_source_from_ctx(ctx_guard)
-            .try_push_edit_and_write_event(ctx_guard)?
-            .map_source_to_ctx(ctx_guard);
-        Ok(ResBody::new(
-            EdlRecordingState::Started,
-            Some(EditBody::Event(edit)),
-        ))
+            .take_current_edit(ctx_guard)?;
+        self.try_log(ctx_guard, edit, origin)
+    }
+
+    // logs an edit already taken at its LTC frame
+    fn try_log(
+        &mut self,
+        ctx_guard: &mut MutexGuard<ContextInner>,
+        edit: Edit,
+        origin: &EventOrigin,
+    ) -> Result<ResBody, Error> {
+        let track = self.track.as_deref();
+        let merged = ctx_guard
+            .edl
+            .as_mut()
+            .context("EDL file does not exist")?
+            .log_edit(track, edit)?;
+        if let Some(merge) = merged.as_ref() {
+            log::info!("Merged edit on track {}: {merge}", track_name(track)?);
+        }
+        let edits = self.try_write_events(ctx_guard, origin)?;
+        if let Some((edit, _)) = edits.first().filter(|_| self.on_main_track()) {
+            edit.map_source_to_ctx(ctx_guard);
+        }
+        Ok(ResBody {
+            merged,
+            ..ResBody::new(EdlRecordingState::Started, Some(EditBody::Log(edits)))
+        })
     }
 
     fn try_start_now(
@@ -468,47 +933,92 @@ impl EditRequestData {
     fn try_log_final_edit(
         &mut self,
         ctx_guard: &mut MutexGuard<ContextInner>,
-    ) -> Result<Vec<Event>, Error> {
+        origin: &EventOrigin,
+    ) -> Result<Vec<(Event, String)>, Error> {
         self.source_tape = None;
         self.av_channels = None;
-        self.try_push_edit_and_write_event(ctx_guard).and_then(|e| {
-            let mut edits = vec![e];
-            match EditType::try_from(self.edit_type.as_str()).unwrap() {
-                EditType::Cut => (),
-                _ => edits
-                    .push(EditRequestData::blank_frame().try_push_edit_and_write_event(ctx_guard)?),
-            };
-            Ok(edits)
-        })
+        self.split = None;
+        self.speed = None;
+        self.try_push_edit_and_write_events(ctx_guard, origin)
+            .and_then(|mut edits| {
+                match EditType::try_from(self.edit_type.as_str()).unwrap() {
+                    EditType::Cut | EditType::Key => (),
+                    _ => edits.extend(
+                        EditRequestData {
+                            track: self.track.clone(),
+                            ..EditRequestData::blank_frame()
+                        }
+                        .try_push_edit_and_write_events(ctx_guard, origin)?,
+                    ),
+                };
+                edits.extend(
+                    ctx_guard
+                        .edl
+                        .as_mut()
+                        .context("EDL file does not exist")?
+                        .end_tracks(self.track.as_deref(), origin)?,
+                );
+                Ok(edits)
+            })
     }
 
-    fn try_push_edit_and_write_event(
+    fn try_push_edit_and_write_events(
         &mut self,
         ctx_guard: &mut MutexGuard<ContextInner>,
-    ) -> Result<Event, Error> {
+        origin: &EventOrigin,
+    ) -> Result<Vec<(Event, String)>, Error> {
         self.try_push_current_edit(ctx_guard)?;
+        self.try_write_events(ctx_guard, origin)
+    }
+
+    // writes the events closed by the edit last pushed on the track
+    fn try_write_events(
+        &self,
+        ctx_guard: &mut MutexGuard<ContextInner>,
+        origin: &EventOrigin,
+    ) -> Result<Vec<(Event, String)>, Error> {
+        let track = self.track.as_deref();
         let edl = ctx_guard.edl.as_mut().context("EDL file does not exist")?;
-        edl.try_build_event()
-            .context("Could not log edit")
-            .and_then(|event| edl.write_event(event))
+        edl.try_build_events(track)
+            .context("Could not log edit")?
+            .into_iter()
+            .map(|event| edl.write_event(track, event, origin))
+            .collect()
     }
 
     fn try_push_current_edit(
         &mut self,
         ctx_guard: &mut MutexGuard<ContextInner>,
     ) -> Result<(), DecodeErr> {
-        let tc = ctx_guard
-            .decode_handlers
-            .recv_frame_timeout(Duration::from_millis(1000))?;
+        let edit = self.take_current_edit(ctx_guard)?;
         ctx_guard
             .edl
             .as_mut()
             .context("EDL file does not exist")?
-            .push_edit(self.take_as_edit(tc)?)
+            .push_edit(self.track.as_deref(), edit)
             .map_err(|e| e.into())
     }
 
+    // the edit at the frame currently decoded
+    fn take_current_edit(
+        &mut self,
+        ctx_guard: &mut MutexGuard<ContextInner>,
+    ) -> Result<Edit, DecodeErr> {
+        let tc = ctx_guard
+            .decode_handlers
+            .recv_frame_timeout(Duration::from_millis(1000))?;
+        Ok(self.take_as_edit(tc)?)
+    }
+
+    // the selected source is for the main track, edits on other tracks name their own
+    fn on_main_track(&self) -> bool {
+        track_name(self.track.as_deref()).is_ok_and(|track| track == MAIN_TRACK)
+    }
+
     fn map_source_from_ctx(&mut self, ctx_guard: &mut MutexGuard<ContextInner>) -> &mut Self {
+        if !self.on_main_track() {
+            return self;
+        }
         if self.source_tape.is_none() {
             self.source_tape = ctx_guard.selected_src_data.source_tape.take();
         }
@@ -523,12 +1033,13 @@ impl EditRequestData {
         let tc = decode_handlers.recv_frame()?;
         self.map_source_from_ctx(&mut ctx.lock());
 
+        let edit = self.take_as_edit(tc)?;
         let mut ctx_guard = ctx.lock();
         ctx_guard
             .edl
             .as_mut()
             .context("EDL file does not exist")?
-            .push_edit(self.take_as_edit(tc)?)?;
+            .push_edit(self.track.as_deref(), edit)?;
 
         log::info!("LTC signal detected. Recording to EDL");
         Ok(ResBody::new(
@@ -539,12 +1050,11 @@ impl EditRequestData {
 }
 
 impl Event {
-    fn map_source_to_ctx(self, ctx_guard: &mut MutexGuard<ContextInner>) -> Self {
-        let source_tape: &SourceTape = (&self).into();
-        let av_channels: AVChannels = (&self).into();
+    fn map_source_to_ctx(&self, ctx_guard: &mut MutexGuard<ContextInner>) {
+        let source_tape: &SourceTape = self.into();
+        let av_channels: AVChannels = self.into();
         ctx_guard.selected_src_data.source_tape = source_tape.into();
         ctx_guard.selected_src_data.av_channels = av_channels.into();
-        self
     }
 }
 
@@ -567,6 +1077,119 @@ impl SourceTapeRequestData {
     }
 }
 
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct SourceTcRequestData {
+    source_tape: String,
+    // the timecode of the tape's recorder on the first edit, or a signed offset from the LTC such
+    // as "-00:00:01:00". Without one the tape's source timecode follows the LTC again.
+    timecode: Option<String>,
+}
+
+impl SourceTcRequestData {
+    fn try_set_source_tc(&self, ctx: &mut Context) -> Result<Response, Error> {
+        let mut ctx_guard = ctx.lock();
+        let rate = framerate(&ctx_guard.opt)?;
+        let source_tc = self
+            .timecode
+            .as_deref()
+            .map(|tc| SourceTc::parse(tc, rate))
+            .transpose()?;
+        if let Some(edl) = ctx_guard.edl.as_mut() {
+            edl.set_source_timecode(&self.source_tape, source_tc);
+        }
+        // kept for the EDLs recorded after this one, and shown and stored as the GUI setting is
+        let opt = &ctx_guard.opt;
+        let mut source_timecodes = opt.source_timecodes.lock();
+        *source_timecodes = source_timecodes
+            .lines()
+            .filter(|line| {
+                line.split_once('=').map(|(tape, _)| tape.trim()) != Some(&self.source_tape)
+            })
+            .map(String::from)
+            .chain(
+                self.timecode
+                    .iter()
+                    .map(|tc| format!("{}={}", self.source_tape, tc)),
+            )
+            .collect::<Vec<_>>()
+            .join("\n");
+        drop(source_timecodes);
+        StoredOpts::SourceTimecodes.write(opt);
+        match self.timecode.as_ref() {
+            Some(tc) => log::info!("Source timecode for {}: {}", self.source_tape, tc),
+            None => log::info!("Source timecode for {} follows LTC", self.source_tape),
+        }
+        Ok(Response::new(serde_json::to_value(self)?, StatusCode::S200))
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct MarkerRequestData {
+    name: String,
+    // one of red, green, blue, cyan, magenta, yellow, black or white
+    color: String,
+    note: Option<String>,
+    duration_frames: Option<u32>,
+}
+
+impl MarkerRequestData {
+    fn try_add_marker(
+        self,
+        ctx_guard: &mut MutexGuard<ContextInner>,
+        origin: &EventOrigin,
+    ) -> Result<Response, Error> {
+        let color = MarkerColor::try_from(self.color.as_str())?;
+        let ltc = ctx_guard
+            .decode_handlers
+            .recv_frame_timeout(Duration::from_millis(1000))
+            .map_err(|e| anyhow!("Error decoding frame: {e}"))?;
+        let marker = ctx_guard
+            .edl
+            .as_mut()
+            .context("EDL file does not exist")?
+            .add_marker(
+                ltc.timecode,
+                color,
+                self.name,
+                self.note,
+                self.duration_frames,
+                origin,
+            )?;
+        log::info!("{}", marker.loc_line());
+        Ok(Response::new(
+            serde_json::to_value(marker)?,
+            StatusCode::S200,
+        ))
+    }
+}
+
+// the fields of a logged edit to change, the rest are kept
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct PatchRequestData {
+    source_tape: Option<String>,
+    edit_type: Option<String>,
+    edit_duration_frames: Option<u32>,
+    wipe_num: Option<u32>,
+    av_channels: Option<AVChannels>,
+}
+
+impl TryFrom<PatchRequestData> for EditPatch {
+    type Error = Error;
+    fn try_from(value: PatchRequestData) -> Result<Self, Self::Error> {
+        Ok(EditPatch {
+            source_tape: value.source_tape,
+            edit_type: value
+                .edit_type
+                .as_deref()
+                .map(EditType::try_from)
+                .transpose()?,
+            edit_duration_frames: value.edit_duration_frames,
+            wipe_num: value.wipe_num,
+            av_channels: value.av_channels,
+        })
+    }
+}
+
 trait IntoResponse {
     type Error;
     fn try_into_200(&self) -> Result<Response, Self::Error>;
@@ -4,9 +4,15 @@ use parking_lot::Mutex;
 use test_support::MockDevice;
 
 use crate::{
-    edl_writer::{AVChannels, Clip, Dissolve, Event, Ntsc, Wipe},
+    edl_writer::{
+        dialect::Dialect, AVChannels, Clip, Dissolve, Event, HandleUnit, Midnight, Ntsc, PauseGap,
+        RecordTimecode, Wipe,
+    },
     ltc_decoder::{config::LTCDevice, LTCListener},
-    server::{EditRequestData, EdlRecordingState, ReqBody, ResBody, Server, SourceTapeRequestData},
+    server::{
+        EditRequestData, EdlRecordingState, MarkerRequestData, PatchRequestData, ReqBody, ResBody,
+        Server, SourceTapeRequestData,
+    },
     state::{Logger, Opt},
     utils::dirs::get_or_make_dir,
 };
@@ -71,6 +77,22 @@ impl MockServer {
             sample_rate: 44_100,
             fps: 30.0,
             ntsc: Ntsc::DropFrame,
+            edl_dialect: Dialect::Cmx3600,
+            rollover_edits: 999,
+            export_formats: vec![],
+            source_labels: String::new(),
+            reel_ids: String::new(),
+            source_timecodes: Default::default(),
+            record_timecode: RecordTimecode::Ltc,
+            record_start: "01:00:00:00".into(),
+            midnight: Midnight::Wrap,
+            pause_gap: PauseGap::LeaveOut,
+            head_handle: 0,
+            tail_handle: 0,
+            handle_unit: HandleUnit::Frames,
+            min_edit_frames: 0,
+            merge_same_source: false,
+            chapter_min_secs: 10,
             buffer_size: Some(device.clone().opt_config.buffer_size),
             input_channel: Some(device.clone().opt_config.input_channel),
             ltc_device: Some(ltc_device.clone()),
@@ -149,6 +171,7 @@ impl AssessEditType for minreq::Response {
             Event::Cut(clip) => clip,
             Event::Dissolve(_) => panic!("Expected Cut, Dissolve"),
             Event::Wipe(_) => panic!("Expected Cut, got Wipe"),
+            Event::Key(_) => panic!("Expected Cut, got Key"),
         }
     }
 
@@ -157,6 +180,7 @@ impl AssessEditType for minreq::Response {
             Event::Dissolve(dis) => dis,
             Event::Wipe(_) => panic!("Expected Dissolve, got Wipe"),
             Event::Cut(_) => panic!("Expected Dissolve, got Cut"),
+            Event::Key(_) => panic!("Expected Dissolve, got Key"),
         }
     }
 
@@ -165,6 +189,7 @@ impl AssessEditType for minreq::Response {
             Event::Wipe(wipe) => wipe,
             Event::Dissolve(_) => panic!("Expected Wipe, Dissolve"),
             Event::Cut(_) => panic!("Expected Wipe, got Cut"),
+            Event::Key(_) => panic!("Expected Wipe, got Key"),
         }
     }
 }
@@ -175,6 +200,7 @@ impl AssessEditType for Event {
             Event::Cut(clip) => clip.clone(),
             Event::Dissolve(_) => panic!("Expected Cut, Dissolve"),
             Event::Wipe(_) => panic!("Expected Cut, got Wipe"),
+            Event::Key(_) => panic!("Expected Cut, got Key"),
         }
     }
 
@@ -183,6 +209,7 @@ impl AssessEditType for Event {
             Event::Dissolve(dis) => dis.clone(),
             Event::Wipe(_) => panic!("Expected Dissolve, got Wipe"),
             Event::Cut(_) => panic!("Expected Dissolve, got Cut"),
+            Event::Key(_) => panic!("Expected Dissolve, got Key"),
         }
     }
 
@@ -191,22 +218,55 @@ impl AssessEditType for Event {
             Event::Wipe(wipe) => wipe.clone(),
             Event::Dissolve(_) => panic!("Expected Wipe, Dissolve"),
             Event::Cut(_) => panic!("Expected Wipe, got Cut"),
+            Event::Key(_) => panic!("Expected Wipe, got Key"),
         }
     }
 }
 
 fn serde_edit(edit: EditRequestData) -> String {
-    serde_json::to_value(&ReqBody::Event(edit))
+    serde_json::to_value(ReqBody::Event(edit))
         .unwrap()
         .to_string()
 }
 
 fn serde_src(src: SourceTapeRequestData) -> String {
-    serde_json::to_value(&ReqBody::Source(src))
+    serde_json::to_value(ReqBody::Source(src))
         .unwrap()
         .to_string()
 }
 
+fn serde_cut(source_tape: &str) -> String {
+    serde_edit(EditRequestData {
+        edit_type: "cut".into(),
+        edit_duration_frames: None,
+        wipe_num: None,
+        key_out_frames: None,
+        source_tape: Some(source_tape.into()),
+        av_channels: Some(AVChannels::default()),
+        split: None,
+        speed: None,
+        track: None,
+    })
+}
+
+fn serde_marker(marker: MarkerRequestData) -> String {
+    serde_json::to_value(ReqBody::Marker(marker))
+        .unwrap()
+        .to_string()
+}
+
+fn serde_patch_tape(source_tape: &str) -> String {
+    serde_json::to_value(ReqBody::Patch(PatchRequestData {
+        source_tape: Some(source_tape.into()),
+        edit_type: None,
+        edit_duration_frames: None,
+        wipe_num: None,
+        av_channels: None,
+    }))
+    .unwrap()
+    .to_string()
+}
+
 #[test]
 fn edit_starts_ends_cut() {
     let MockServer {
@@ -223,8 +283,12 @@ fn edit_starts_ends_cut() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::new(false, 1)),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -240,8 +304,12 @@ fn edit_starts_ends_cut() {
             edit_type: "wipe".into(),
             edit_duration_frames: Some(15),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -259,8 +327,12 @@ fn edit_starts_ends_cut() {
             edit_type: "cut".into(),
             edit_duration_frames: Some(1), // ignored
             wipe_num: Some(1),             // ignored
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -283,8 +355,12 @@ fn edit_starts_ends_cut() {
             edit_type: "wipe".into(),
             edit_duration_frames: Some(20),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::new(false, 3)),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -302,8 +378,12 @@ fn edit_starts_ends_cut() {
             edit_type: "dissolve".into(),
             edit_duration_frames: Some(10),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -326,8 +406,12 @@ fn edit_starts_ends_cut() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape3".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -352,8 +436,12 @@ fn edit_starts_ends_cut() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: Some(1), // ignored
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -368,8 +456,12 @@ fn edit_starts_ends_cut() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::new(true, 4)),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -404,8 +496,12 @@ fn edit_starts_cut_ends_diss() {
             edit_type: "cut".into(),
             edit_duration_frames: Some(40),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::new(false, 1)),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -421,8 +517,12 @@ fn edit_starts_cut_ends_diss() {
             edit_type: "wipe".into(),
             edit_duration_frames: Some(15),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -440,8 +540,12 @@ fn edit_starts_cut_ends_diss() {
             edit_type: "cut".into(),
             edit_duration_frames: Some(1), // ignored
             wipe_num: Some(1),             // ignored
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -464,8 +568,12 @@ fn edit_starts_cut_ends_diss() {
             edit_type: "wipe".into(),
             edit_duration_frames: Some(20),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::new(false, 3)),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -483,8 +591,12 @@ fn edit_starts_cut_ends_diss() {
             edit_type: "dissolve".into(),
             edit_duration_frames: Some(10),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -507,8 +619,12 @@ fn edit_starts_cut_ends_diss() {
             edit_type: "cut".into(),
             edit_duration_frames: Some(1), // ignored
             wipe_num: Some(1),             // ignored
+            key_out_frames: None,
             source_tape: Some("tape3".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -533,8 +649,12 @@ fn edit_starts_cut_ends_diss() {
             edit_type: "cut".into(),
             edit_duration_frames: Some(1), // ignored
             wipe_num: Some(1),             // ignored
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -549,8 +669,12 @@ fn edit_starts_cut_ends_diss() {
             edit_type: "dissolve".into(),
             edit_duration_frames: Some(42),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::new(true, 4)),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -609,8 +733,12 @@ fn edit_starts_diss_ends_cut() {
             edit_type: "dissolve".into(),
             edit_duration_frames: Some(40),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::new(false, 1)),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -626,8 +754,12 @@ fn edit_starts_diss_ends_cut() {
             edit_type: "wipe".into(),
             edit_duration_frames: Some(15),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -656,8 +788,12 @@ fn edit_starts_diss_ends_cut() {
             edit_type: "cut".into(),
             edit_duration_frames: Some(1), // ignored
             wipe_num: Some(1),             // ignored
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -680,8 +816,12 @@ fn edit_starts_diss_ends_cut() {
             edit_type: "wipe".into(),
             edit_duration_frames: Some(20),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::new(false, 3)),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -699,8 +839,12 @@ fn edit_starts_diss_ends_cut() {
             edit_type: "dissolve".into(),
             edit_duration_frames: Some(10),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -723,8 +867,12 @@ fn edit_starts_diss_ends_cut() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -775,8 +923,12 @@ fn event_failures() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -794,8 +946,12 @@ fn event_failures() {
             edit_type: "swipe".into(), //invalid
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -824,8 +980,12 @@ fn wait_for_ltc_on_start() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -838,8 +998,12 @@ fn wait_for_ltc_on_start() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -855,8 +1019,12 @@ fn wait_for_ltc_on_start() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -892,8 +1060,12 @@ fn edit_events_with_preselected_src_basic() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -908,8 +1080,12 @@ fn edit_events_with_preselected_src_basic() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -922,8 +1098,12 @@ fn edit_events_with_preselected_src_basic() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -959,8 +1139,12 @@ fn select_src_and_alternate() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -985,8 +1169,12 @@ fn select_src_and_alternate() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1000,8 +1188,12 @@ fn select_src_and_alternate() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1015,8 +1207,12 @@ fn select_src_and_alternate() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1030,8 +1226,12 @@ fn select_src_and_alternate() {
             edit_type: "dissolve".into(),
             edit_duration_frames: Some(10),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1045,8 +1245,12 @@ fn select_src_and_alternate() {
             edit_type: "cut".into(),
             edit_duration_frames: Some(10),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1067,8 +1271,12 @@ fn select_src_and_alternate() {
             edit_type: "wipe".into(),
             edit_duration_frames: Some(5),
             wipe_num: Some(1),
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1082,8 +1290,12 @@ fn select_src_and_alternate() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1104,10 +1316,14 @@ fn select_src_and_alternate() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
 
             // change source
             source_tape: Some("tape2".into()),
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1121,8 +1337,12 @@ fn select_src_and_alternate() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1136,8 +1356,12 @@ fn select_src_and_alternate() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1178,8 +1402,12 @@ fn edit_events_with_preselected_src_with_disssolve() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1194,8 +1422,12 @@ fn edit_events_with_preselected_src_with_disssolve() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1208,8 +1440,12 @@ fn edit_events_with_preselected_src_with_disssolve() {
             edit_type: "dissolve".into(),
             edit_duration_frames: Some(10),
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1266,8 +1502,12 @@ fn select_src_while_waiting() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1280,8 +1520,12 @@ fn select_src_while_waiting() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape2".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1295,8 +1539,12 @@ fn select_src_while_waiting() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1309,8 +1557,12 @@ fn select_src_while_waiting() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1324,8 +1576,12 @@ fn select_src_while_waiting() {
                 edit_type: "cut".into(),
                 edit_duration_frames: None,
                 wipe_num: None,
+                key_out_frames: None,
                 source_tape: None,
                 av_channels: Some(AVChannels::default()),
+                split: None,
+                speed: None,
+                track: None,
             }))
             .send()
             .unwrap();
@@ -1353,8 +1609,12 @@ fn select_src_while_waiting() {
                 edit_type: "cut".into(),
                 edit_duration_frames: None,
                 wipe_num: None,
+                key_out_frames: None,
                 source_tape: None,
                 av_channels: Some(AVChannels::default()),
+                split: None,
+                speed: None,
+                track: None,
             }))
             .send()
             .unwrap();
@@ -1379,8 +1639,12 @@ fn select_src_while_waiting() {
                 edit_type: "cut".into(),
                 edit_duration_frames: None,
                 wipe_num: None,
+                key_out_frames: None,
                 source_tape: None,
                 av_channels: Some(AVChannels::default()),
+                split: None,
+                speed: None,
+                track: None,
             }))
             .send()
             .unwrap();
@@ -1394,8 +1658,12 @@ fn select_src_while_waiting() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1421,8 +1689,12 @@ fn event_non_ready() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1435,8 +1707,12 @@ fn event_non_ready() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1457,8 +1733,12 @@ fn event_non_ready() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1478,8 +1758,12 @@ fn event_non_ready() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1492,8 +1776,12 @@ fn event_non_ready() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1506,8 +1794,12 @@ fn event_non_ready() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: Some("tape1".into()),
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1541,8 +1833,12 @@ fn event_repeats() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1555,8 +1851,12 @@ fn event_repeats() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1569,8 +1869,12 @@ fn event_repeats() {
             edit_type: "cut".into(),
             edit_duration_frames: None,
             wipe_num: None,
+            key_out_frames: None,
             source_tape: None,
             av_channels: None,
+            split: None,
+            speed: None,
+            track: None,
         }))
         .send()
         .unwrap();
@@ -1584,8 +1888,12 @@ fn event_repeats() {
                 edit_type: "cut".into(),
                 edit_duration_frames: None,
                 wipe_num: None,
+                key_out_frames: None,
                 source_tape: Some(i.to_string()),
                 av_channels: Some(AVChannels::default()),
+                split: None,
+                speed: None,
+                track: None,
             }))
             .send()
             .unwrap();
@@ -1603,8 +1911,12 @@ fn event_repeats() {
                 edit_type: "cut".into(),
                 edit_duration_frames: None,
                 wipe_num: None,
+                key_out_frames: None,
                 source_tape: Some(i.to_string()),
                 av_channels: Some(AVChannels::default()),
+                split: None,
+                speed: None,
+                track: None,
             }))
             .send()
             .unwrap();
@@ -1614,3 +1926,344 @@ fn event_repeats() {
 
     tx_stop_serv.send(()).unwrap();
 }
+
+#[test]
+fn marker_status() {
+    let MockServer {
+        device,
+        port,
+        tx_stop_serv,
+    } = MockServer::new("marker_status".to_string()).server_ready();
+
+    let marker = |color: &str| MarkerRequestData {
+        name: "great moment".into(),
+        color: color.into(),
+        note: None,
+        duration_frames: None,
+    };
+
+    let before_start = minreq::post(format!("http://127.0.0.1:{port}/marker"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_marker(marker("red")))
+        .send()
+        .unwrap();
+    assert_eq!(before_start.status_code, 202);
+    assert_eq!(before_start.rec_state(), EdlRecordingState::Stopped);
+
+    device.tx_start_playing.send(()).unwrap();
+    minreq::post(format!("http://127.0.0.1:{port}/start"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape1"))
+        .send()
+        .unwrap();
+    wait_rec_state_started(port);
+
+    let marker_res = minreq::post(format!("http://127.0.0.1:{port}/marker"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_marker(marker("red")))
+        .send()
+        .unwrap();
+    assert_eq!(marker_res.status_code, 200);
+    let marker_json = marker_res.json::<serde_json::Value>().unwrap();
+    assert_eq!(marker_json["name"], "great moment");
+    assert_eq!(marker_json["color"], "RED");
+
+    let bad_color = minreq::post(format!("http://127.0.0.1:{port}/marker"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_marker(marker("orange")))
+        .send()
+        .unwrap();
+    assert_eq!(bad_color.status_code, 500);
+
+    let wrong_body = minreq::post(format!("http://127.0.0.1:{port}/marker"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape2"))
+        .send()
+        .unwrap();
+    assert_eq!(wrong_body.status_code, 500);
+
+    let end_res = minreq::post(format!("http://127.0.0.1:{port}/end"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape2"))
+        .send()
+        .unwrap();
+    assert_eq!(end_res.status_code, 200);
+
+    tx_stop_serv.send(()).unwrap();
+}
+
+#[test]
+fn correction_status() {
+    let MockServer {
+        device,
+        port,
+        tx_stop_serv,
+    } = MockServer::new("correction_status".to_string()).server_ready();
+
+    let before_start = minreq::delete(format!("http://127.0.0.1:{port}/edits/last"))
+        .send()
+        .unwrap();
+    assert_eq!(before_start.status_code, 202);
+    assert_eq!(before_start.rec_state(), EdlRecordingState::Stopped);
+
+    device.tx_start_playing.send(()).unwrap();
+    minreq::post(format!("http://127.0.0.1:{port}/start"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape1"))
+        .send()
+        .unwrap();
+    wait_rec_state_started(port);
+
+    for tape in ["tape2", "tape3"] {
+        let log_res = minreq::post(format!("http://127.0.0.1:{port}/log"))
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_cut(tape))
+            .send()
+            .unwrap();
+        assert_eq!(log_res.status_code, 200);
+    }
+
+    let patch_res = minreq::patch(format!("http://127.0.0.1:{port}/edits/last"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_patch_tape("tape4"))
+        .send()
+        .unwrap();
+    assert_eq!(patch_res.status_code, 200);
+    let patched = patch_res
+        .json::<ResBody>()
+        .unwrap()
+        .corrected_edits
+        .unwrap();
+    assert_eq!(
+        patched[0].cut().source_tape.to_string(),
+        "tape4".to_string()
+    );
+
+    let delete_res = minreq::delete(format!("http://127.0.0.1:{port}/edits/1"))
+        .send()
+        .unwrap();
+    assert_eq!(delete_res.status_code, 200);
+    let deleted = delete_res.json::<ResBody>().unwrap().deleted_edit.unwrap();
+    assert_eq!(deleted.cut().source_tape.to_string(), "tape1".to_string());
+
+    // edits that haven't been written, tracks without edits and the open edit aren't found
+    for path in [
+        "/edits/9",
+        "/edits/0",
+        "/edits/open",
+        "/edits/last?track=V9",
+    ] {
+        let res = minreq::delete(format!("http://127.0.0.1:{port}{path}"))
+            .send()
+            .unwrap();
+        assert_eq!(res.status_code, 404, "DELETE {path}");
+    }
+    for path in ["/edits/9", "/edits/last?track=V9", "/edits/last?track=V-2"] {
+        let res = minreq::patch(format!("http://127.0.0.1:{port}{path}"))
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_patch_tape("tape5"))
+            .send()
+            .unwrap();
+        assert_eq!(res.status_code, 404, "PATCH {path}");
+    }
+
+    let wrong_body = minreq::patch(format!("http://127.0.0.1:{port}/edits/last"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape5"))
+        .send()
+        .unwrap();
+    assert_eq!(wrong_body.status_code, 500);
+
+    let end_res = minreq::post(format!("http://127.0.0.1:{port}/end"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape5"))
+        .send()
+        .unwrap();
+    assert_eq!(end_res.status_code, 200);
+
+    tx_stop_serv.send(()).unwrap();
+}
+
+#[test]
+fn open_edit_status() {
+    let MockServer {
+        device,
+        port,
+        tx_stop_serv,
+    } = MockServer::new("open_edit_status".to_string()).server_ready();
+
+    let before_start = minreq::get(format!("http://127.0.0.1:{port}/edits/open"))
+        .send()
+        .unwrap();
+    assert_eq!(before_start.status_code, 202);
+    assert_eq!(before_start.rec_state(), EdlRecordingState::Stopped);
+
+    device.tx_start_playing.send(()).unwrap();
+    minreq::post(format!("http://127.0.0.1:{port}/start"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape1"))
+        .send()
+        .unwrap();
+    wait_rec_state_started(port);
+
+    let open_res = minreq::get(format!("http://127.0.0.1:{port}/edits/open"))
+        .send()
+        .unwrap();
+    assert_eq!(open_res.status_code, 200);
+    let open_json = open_res.json::<serde_json::Value>().unwrap();
+    assert_eq!(open_json["track"], "V1");
+    assert_eq!(open_json["open_edits"][0]["source_tape"], "tape1");
+
+    let amend_res = minreq::patch(format!("http://127.0.0.1:{port}/edits/open"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_patch_tape("tape2"))
+        .send()
+        .unwrap();
+    assert_eq!(amend_res.status_code, 200);
+    let amend_json = amend_res.json::<serde_json::Value>().unwrap();
+    assert_eq!(amend_json["open_edits"][0]["source_tape"], "tape2");
+
+    // a track without edits has nothing open
+    let other_track = minreq::get(format!("http://127.0.0.1:{port}/edits/open?track=V9"))
+        .send()
+        .unwrap();
+    assert_eq!(other_track.status_code, 404);
+    let other_track = minreq::patch(format!("http://127.0.0.1:{port}/edits/open?track=V9"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_patch_tape("tape3"))
+        .send()
+        .unwrap();
+    assert_eq!(other_track.status_code, 404);
+
+    let wrong_body = minreq::patch(format!("http://127.0.0.1:{port}/edits/open"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape3"))
+        .send()
+        .unwrap();
+    assert_eq!(wrong_body.status_code, 500);
+
+    let end_res = minreq::post(format!("http://127.0.0.1:{port}/end"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape3"))
+        .send()
+        .unwrap();
+    assert_eq!(end_res.status_code, 200);
+    let final_edits = end_res.final_edits();
+    assert_eq!(
+        final_edits[0].cut().source_tape.to_string(),
+        "tape2".to_string()
+    );
+
+    tx_stop_serv.send(()).unwrap();
+}
+
+#[test]
+fn pause_resume_status() {
+    let MockServer {
+        device,
+        port,
+        tx_stop_serv,
+    } = MockServer::new("pause_resume_status".to_string()).server_ready();
+
+    let before_start = minreq::post(format!("http://127.0.0.1:{port}/pause"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape1"))
+        .send()
+        .unwrap();
+    assert_eq!(before_start.status_code, 202);
+    assert_eq!(before_start.rec_state(), EdlRecordingState::Stopped);
+    let before_start = minreq::post(format!("http://127.0.0.1:{port}/resume"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape1"))
+        .send()
+        .unwrap();
+    assert_eq!(before_start.status_code, 202);
+    assert_eq!(before_start.rec_state(), EdlRecordingState::Stopped);
+
+    device.tx_start_playing.send(()).unwrap();
+    minreq::post(format!("http://127.0.0.1:{port}/start"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape1"))
+        .send()
+        .unwrap();
+    wait_rec_state_started(port);
+
+    let not_paused = minreq::post(format!("http://127.0.0.1:{port}/resume"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape2"))
+        .send()
+        .unwrap();
+    assert_eq!(not_paused.status_code, 202);
+    assert_eq!(not_paused.rec_state(), EdlRecordingState::Started);
+
+    let pause_res = minreq::post(format!("http://127.0.0.1:{port}/pause"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape2"))
+        .send()
+        .unwrap();
+    assert_eq!(pause_res.status_code, 200);
+    assert_eq!(pause_res.rec_state(), EdlRecordingState::Paused);
+    assert_eq!(
+        pause_res.final_edits()[0].cut().source_tape.to_string(),
+        "tape1".to_string()
+    );
+
+    let paused_again = minreq::post(format!("http://127.0.0.1:{port}/pause"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape2"))
+        .send()
+        .unwrap();
+    assert_eq!(paused_again.status_code, 202);
+    assert_eq!(paused_again.rec_state(), EdlRecordingState::Paused);
+
+    let log_paused = minreq::post(format!("http://127.0.0.1:{port}/log"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape2"))
+        .send()
+        .unwrap();
+    assert_eq!(log_paused.status_code, 202);
+    assert_eq!(log_paused.rec_state(), EdlRecordingState::Paused);
+
+    // the recording stays paused when the resume edit is invalid
+    let bad_resume = minreq::post(format!("http://127.0.0.1:{port}/resume"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_edit(EditRequestData {
+            edit_type: "wipe".into(),
+            edit_duration_frames: None,
+            wipe_num: None,
+            key_out_frames: None,
+            source_tape: Some("tape2".into()),
+            av_channels: Some(AVChannels::default()),
+            split: None,
+            speed: None,
+            track: None,
+        }))
+        .send()
+        .unwrap();
+    assert_eq!(bad_resume.status_code, 500);
+    let state_res = minreq::get(format!("http://127.0.0.1:{port}/edl-recording-state"))
+        .send()
+        .unwrap();
+    assert_eq!(state_res.rec_state(), EdlRecordingState::Paused);
+
+    let resume_res = minreq::post(format!("http://127.0.0.1:{port}/resume"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape2"))
+        .send()
+        .unwrap();
+    assert_eq!(resume_res.status_code, 200);
+    assert_eq!(resume_res.rec_state(), EdlRecordingState::Started);
+
+    let end_res = minreq::post(format!("http://127.0.0.1:{port}/end"))
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_cut("tape3"))
+        .send()
+        .unwrap();
+    assert_eq!(end_res.status_code, 200);
+    assert_eq!(
+        end_res.final_edits()[0].cut().source_tape.to_string(),
+        "tape2".to_string()
+    );
+
+    tx_stop_serv.send(()).unwrap();
+}
@@ -13,7 +13,8 @@ use std::{
 };
 
 use crate::{
-    edl_writer::Ntsc,
+    edl_writer::{dialect::Dialect, HandleUnit, Midnight, Ntsc, PauseGap, RecordTimecode},
+    export::ExportFormat,
     ltc_decoder::config::{LTCConfig, LTCDevice, LTCDeviceName, LTCHostId},
     utils::dirs as dir_utils,
 };
@@ -78,6 +79,34 @@ pub struct Opt {
     pub sample_rate: usize,
     pub fps: f32,
     pub ntsc: Ntsc,
+    pub edl_dialect: Dialect,
+    // edits per EDL file before recording continues in a `_part{n}` file
+    pub rollover_edits: usize,
+    pub export_formats: Vec<ExportFormat>,
+    // one `tape=label` per line, used in place of the tape name for chapter titles
+    pub source_labels: String,
+    // one `tape=REEL` per line, written to the reel field in place of a shortened tape name
+    pub reel_ids: String,
+    // one `tape=timecode` per line, the timecode of the tape's recorder on the first edit, or an
+    // offset from the LTC when signed, ie. `iso1=-00:00:01:00`. Shared with the server, which
+    // sets it over the API while the GUI is locked.
+    pub source_timecodes: Arc<Mutex<String>>,
+    // record in and out points from the LTC, or a continuous timeline from `record_start`
+    pub record_timecode: RecordTimecode,
+    pub record_start: String,
+    // how timecodes are written once time-of-day LTC wraps through midnight
+    pub midnight: Midnight,
+    // what's written over the time a recording is paused
+    pub pause_gap: PauseGap,
+    // source frames or seconds widening each clip before and after, for editors to trim
+    pub head_handle: u32,
+    pub tail_handle: u32,
+    pub handle_unit: HandleUnit,
+    // double-taps on the switcher: edits shorter than this many frames are taken over by the next
+    // edit, and cuts to the source already recording are dropped when merging
+    pub min_edit_frames: u32,
+    pub merge_same_source: bool,
+    pub chapter_min_secs: u32,
 
     // TODO: just take LTCConfg? we're just duplicating its structure + the arcs which we can just
     // move to that type anways.
@@ -117,6 +146,80 @@ impl Opt {
         StoredOpts::Ntsc.try_into().unwrap_or(Ntsc::NonDropFrame)
     }
 
+    fn default_edl_dialect() -> Dialect {
+        StoredOpts::EdlDialect
+            .try_into()
+            .unwrap_or(Dialect::Cmx3600)
+    }
+
+    fn default_rollover_edits() -> usize {
+        StoredOpts::RolloverEdits.try_into().unwrap_or(999)
+    }
+
+    fn default_export_formats() -> Vec<ExportFormat> {
+        StoredOpts::ExportFormats.try_into().unwrap_or_default()
+    }
+
+    fn default_source_labels() -> String {
+        StoredOpts::SourceLabels.try_into().unwrap_or_default()
+    }
+
+    fn default_reel_ids() -> String {
+        StoredOpts::ReelIds.try_into().unwrap_or_default()
+    }
+
+    fn default_source_timecodes() -> String {
+        StoredOpts::SourceTimecodes.try_into().unwrap_or_default()
+    }
+
+    fn default_record_timecode() -> RecordTimecode {
+        StoredOpts::RecordTimecode
+            .try_into()
+            .unwrap_or(RecordTimecode::Ltc)
+    }
+
+    fn default_record_start() -> String {
+        StoredOpts::RecordStart
+            .try_into()
+            .unwrap_or_else(|_| "01:00:00:00".into())
+    }
+
+    fn default_midnight() -> Midnight {
+        StoredOpts::Midnight.try_into().unwrap_or(Midnight::Wrap)
+    }
+
+    fn default_pause_gap() -> PauseGap {
+        StoredOpts::PauseGap
+            .try_into()
+            .unwrap_or(PauseGap::LeaveOut)
+    }
+
+    fn default_head_handle() -> u32 {
+        StoredOpts::HeadHandle.try_into().unwrap_or_default()
+    }
+
+    fn default_tail_handle() -> u32 {
+        StoredOpts::TailHandle.try_into().unwrap_or_default()
+    }
+
+    fn default_handle_unit() -> HandleUnit {
+        StoredOpts::HandleUnit
+            .try_into()
+            .unwrap_or(HandleUnit::Frames)
+    }
+
+    fn default_min_edit_frames() -> u32 {
+        StoredOpts::MinEditFrames.try_into().unwrap_or_default()
+    }
+
+    fn default_merge_same_source() -> bool {
+        StoredOpts::MergeSameSource.try_into().unwrap_or_default()
+    }
+
+    fn default_chapter_min_secs() -> u32 {
+        StoredOpts::ChapterMinSecs.try_into().unwrap_or(10)
+    }
+
     fn default_ltc() -> LTCSerializedConfg {
         LTCSerializedConfg {
             device: StoredOpts::LTCDevice.try_into().ok(),
@@ -144,6 +247,22 @@ impl Default for Opt {
             sample_rate: Opt::default_sample_rate(),
             fps: Opt::default_frame_rate(),
             ntsc: Opt::default_ntsc(),
+            edl_dialect: Opt::default_edl_dialect(),
+            rollover_edits: Opt::default_rollover_edits(),
+            export_formats: Opt::default_export_formats(),
+            source_labels: Opt::default_source_labels(),
+            reel_ids: Opt::default_reel_ids(),
+            source_timecodes: Arc::new(Mutex::new(Opt::default_source_timecodes())),
+            record_timecode: Opt::default_record_timecode(),
+            record_start: Opt::default_record_start(),
+            midnight: Opt::default_midnight(),
+            pause_gap: Opt::default_pause_gap(),
+            head_handle: Opt::default_head_handle(),
+            tail_handle: Opt::default_tail_handle(),
+            handle_unit: Opt::default_handle_unit(),
+            min_edit_frames: Opt::default_min_edit_frames(),
+            merge_same_source: Opt::default_merge_same_source(),
+            chapter_min_secs: Opt::default_chapter_min_secs(),
             ltc_devices,
             buffer_size,
             input_channel,
@@ -202,12 +321,30 @@ impl Writer for u16 {
     }
 }
 
+impl Writer for u32 {
+    fn write(&self, key: &StoredOpts) -> Option<IVec> {
+        DB.insert_from_opts(key, itoa::Buffer::new().format(*self))
+    }
+}
+
+impl Writer for bool {
+    fn write(&self, key: &StoredOpts) -> Option<IVec> {
+        DB.insert_from_opts(key, self.to_string().as_bytes())
+    }
+}
+
 impl Writer for f32 {
     fn write(&self, key: &StoredOpts) -> Option<IVec> {
         DB.insert_from_opts(key, self.to_string().as_bytes())
     }
 }
 
+impl Writer for String {
+    fn write(&self, key: &StoredOpts) -> Option<IVec> {
+        DB.insert_from_opts(key, self.as_bytes())
+    }
+}
+
 impl Writer for PathBuf {
     fn write(&self, key: &StoredOpts) -> Option<IVec> {
         DB.insert_from_opts(key, self.to_str()?)
@@ -220,6 +357,43 @@ impl Writer for Ntsc {
     }
 }
 
+impl Writer for RecordTimecode {
+    fn write(&self, key: &StoredOpts) -> Option<IVec> {
+        DB.insert_from_opts(key, <&str>::from(*self))
+    }
+}
+
+impl Writer for Midnight {
+    fn write(&self, key: &StoredOpts) -> Option<IVec> {
+        DB.insert_from_opts(key, <&str>::from(*self))
+    }
+}
+
+impl Writer for PauseGap {
+    fn write(&self, key: &StoredOpts) -> Option<IVec> {
+        DB.insert_from_opts(key, <&str>::from(*self))
+    }
+}
+
+impl Writer for HandleUnit {
+    fn write(&self, key: &StoredOpts) -> Option<IVec> {
+        DB.insert_from_opts(key, <&str>::from(*self))
+    }
+}
+
+impl Writer for Dialect {
+    fn write(&self, key: &StoredOpts) -> Option<IVec> {
+        DB.insert_from_opts(key, <&str>::from(*self))
+    }
+}
+
+impl Writer for Vec<ExportFormat> {
+    fn write(&self, key: &StoredOpts) -> Option<IVec> {
+        let formats: Vec<&str> = self.iter().map(|format| (*format).into()).collect();
+        DB.insert_from_opts(key, formats.join(",").as_bytes())
+    }
+}
+
 // we use unwrap_or_default to find values which should never match a valid config.
 // this way they're always looked up according the device and set to default from
 // there if they do not exist
@@ -264,6 +438,22 @@ pub enum StoredOpts {
     LTCHostId,
     BufferSize,
     InputChannel,
+    ExportFormats,
+    SourceLabels,
+    ChapterMinSecs,
+    EdlDialect,
+    RolloverEdits,
+    ReelIds,
+    SourceTimecodes,
+    RecordTimecode,
+    RecordStart,
+    PauseGap,
+    HeadHandle,
+    TailHandle,
+    HandleUnit,
+    MinEditFrames,
+    MergeSameSource,
+    Midnight,
 }
 
 impl StoredOpts {
@@ -278,6 +468,22 @@ impl StoredOpts {
             StoredOpts::BufferSize => &[6],
             StoredOpts::InputChannel => &[7],
             StoredOpts::LTCHostId => &[8],
+            StoredOpts::ExportFormats => &[9],
+            StoredOpts::SourceLabels => &[10],
+            StoredOpts::ChapterMinSecs => &[11],
+            StoredOpts::EdlDialect => &[12],
+            StoredOpts::RolloverEdits => &[13],
+            StoredOpts::ReelIds => &[14],
+            StoredOpts::SourceTimecodes => &[15],
+            StoredOpts::RecordTimecode => &[16],
+            StoredOpts::RecordStart => &[17],
+            StoredOpts::PauseGap => &[18],
+            StoredOpts::HeadHandle => &[19],
+            StoredOpts::TailHandle => &[20],
+            StoredOpts::HandleUnit => &[21],
+            StoredOpts::MinEditFrames => &[22],
+            StoredOpts::MergeSameSource => &[23],
+            StoredOpts::Midnight => &[24],
         }
     }
 
@@ -292,6 +498,22 @@ impl StoredOpts {
             t @ StoredOpts::LTCHostId => opt.ltc_host.write(t),
             t @ StoredOpts::BufferSize => opt.buffer_size.write(t),
             t @ StoredOpts::InputChannel => opt.input_channel.write(t),
+            t @ StoredOpts::ExportFormats => opt.export_formats.write(t),
+            t @ StoredOpts::SourceLabels => opt.source_labels.write(t),
+            t @ StoredOpts::ChapterMinSecs => opt.chapter_min_secs.write(t),
+            t @ StoredOpts::EdlDialect => opt.edl_dialect.write(t),
+            t @ StoredOpts::RolloverEdits => opt.rollover_edits.write(t),
+            t @ StoredOpts::ReelIds => opt.reel_ids.write(t),
+            t @ StoredOpts::SourceTimecodes => opt.source_timecodes.lock().write(t),
+            t @ StoredOpts::RecordTimecode => opt.record_timecode.write(t),
+            t @ StoredOpts::RecordStart => opt.record_start.write(t),
+            t @ StoredOpts::PauseGap => opt.pause_gap.write(t),
+            t @ StoredOpts::HeadHandle => opt.head_handle.write(t),
+            t @ StoredOpts::TailHandle => opt.tail_handle.write(t),
+            t @ StoredOpts::HandleUnit => opt.handle_unit.write(t),
+            t @ StoredOpts::MinEditFrames => opt.min_edit_frames.write(t),
+            t @ StoredOpts::MergeSameSource => opt.merge_same_source.write(t),
+            t @ StoredOpts::Midnight => opt.midnight.write(t),
         }
     }
 }
@@ -329,6 +551,17 @@ impl TryFrom<StoredOpts> for u32 {
     }
 }
 
+impl TryFrom<StoredOpts> for bool {
+    type Error = Error;
+    fn try_from(stored_opts: StoredOpts) -> Result<Self, Self::Error> {
+        DB.get_from_stored_opts(stored_opts).and_then(|val| {
+            str::from_utf8(&val)?
+                .parse::<bool>()
+                .context("Could not parse to bool")
+        })
+    }
+}
+
 impl TryFrom<StoredOpts> for f32 {
     type Error = Error;
     fn try_from(stored_opts: StoredOpts) -> Result<Self, Self::Error> {
@@ -358,6 +591,65 @@ impl TryFrom<StoredOpts> for Ntsc {
     }
 }
 
+impl TryFrom<StoredOpts> for RecordTimecode {
+    type Error = Error;
+    fn try_from(stored_opts: StoredOpts) -> Result<Self, Self::Error> {
+        DB.get_from_stored_opts(stored_opts).and_then(|val| {
+            RecordTimecode::try_from(str::from_utf8(&val).context("Could not parse to utf8 str")?)
+        })
+    }
+}
+
+impl TryFrom<StoredOpts> for Midnight {
+    type Error = Error;
+    fn try_from(stored_opts: StoredOpts) -> Result<Self, Self::Error> {
+        DB.get_from_stored_opts(stored_opts).and_then(|val| {
+            Midnight::try_from(str::from_utf8(&val).context("Could not parse to utf8 str")?)
+        })
+    }
+}
+
+impl TryFrom<StoredOpts> for PauseGap {
+    type Error = Error;
+    fn try_from(stored_opts: StoredOpts) -> Result<Self, Self::Error> {
+        DB.get_from_stored_opts(stored_opts).and_then(|val| {
+            PauseGap::try_from(str::from_utf8(&val).context("Could not parse to utf8 str")?)
+        })
+    }
+}
+
+impl TryFrom<StoredOpts> for HandleUnit {
+    type Error = Error;
+    fn try_from(stored_opts: StoredOpts) -> Result<Self, Self::Error> {
+        DB.get_from_stored_opts(stored_opts).and_then(|val| {
+            HandleUnit::try_from(str::from_utf8(&val).context("Could not parse to utf8 str")?)
+        })
+    }
+}
+
+impl TryFrom<StoredOpts> for Dialect {
+    type Error = Error;
+    fn try_from(stored_opts: StoredOpts) -> Result<Self, Self::Error> {
+        DB.get_from_stored_opts(stored_opts).and_then(|val| {
+            Dialect::try_from(str::from_utf8(&val).context("Could not parse to utf8 str")?)
+        })
+    }
+}
+
+impl TryFrom<StoredOpts> for Vec<ExportFormat> {
+    type Error = Error;
+    fn try_from(stored_opts: StoredOpts) -> Result<Self, Self::Error> {
+        DB.get_from_stored_opts(stored_opts).and_then(|val| {
+            str::from_utf8(&val)
+                .context("Could not parse to utf8 str")?
+                .split(',')
+                .filter(|format| !format.is_empty())
+                .map(ExportFormat::try_from)
+                .collect()
+        })
+    }
+}
+
 impl TryFrom<StoredOpts> for LTCDeviceName {
     type Error = Error;
     fn try_from(stored_opts: StoredOpts) -> Result<Self, Self::Error> {